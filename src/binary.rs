@@ -1,6 +1,7 @@
 //! Binary path resolution and hashing helpers.
 
 use anyhow::{anyhow, Context, Result};
+use filetime::FileTime;
 use std::env;
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
@@ -88,3 +89,20 @@ fn normalize_exec_path(path: &Path) -> Result<PathBuf> {
 pub(crate) fn hash_binary(path: &Path) -> Result<String> {
     sha256_file(path).context("hash binary")
 }
+
+/// File size and modification time of the resolved binary.
+pub(crate) struct BinaryStat {
+    pub(crate) size_bytes: u64,
+    pub(crate) mtime: Option<i64>,
+}
+
+/// Stat the resolved binary for size and mtime (informational; the hash is canonical).
+pub(crate) fn stat_binary(path: &Path) -> Result<BinaryStat> {
+    let metadata =
+        fs::metadata(path).with_context(|| format!("stat binary {}", path.display()))?;
+    let mtime = FileTime::from_last_modification_time(&metadata).unix_seconds();
+    Ok(BinaryStat {
+        size_bytes: metadata.len(),
+        mtime: Some(mtime),
+    })
+}
@@ -9,6 +9,10 @@ pub(crate) const ENV_LC_ALL: &str = "C";
 pub(crate) const ENV_TZ: &str = "UTC";
 /// `TERM` value enforced for non-interactive output.
 pub(crate) const ENV_TERM: &str = "dumb";
+/// `NO_COLOR` value enforced so tools that honor it emit no ANSI color.
+pub(crate) const ENV_NO_COLOR: &str = "1";
+/// `CLICOLOR` value enforced for tools that follow the BSD convention.
+pub(crate) const ENV_CLICOLOR: &str = "0";
 /// Minimal `PATH` exposed inside the sandbox.
 pub(crate) const ENV_PATH: &str = "/bin:/usr/bin";
 
@@ -21,6 +25,10 @@ pub(crate) struct EnvContract {
     pub(crate) tz: String,
     #[serde(rename = "TERM")]
     pub(crate) term: String,
+    #[serde(rename = "NO_COLOR")]
+    pub(crate) no_color: String,
+    #[serde(rename = "CLICOLOR")]
+    pub(crate) clicolor: String,
 }
 
 /// Return the canonical environment contract for metadata.
@@ -29,14 +37,43 @@ pub(crate) fn env_contract() -> EnvContract {
         lc_all: ENV_LC_ALL.to_string(),
         tz: ENV_TZ.to_string(),
         term: ENV_TERM.to_string(),
+        no_color: ENV_NO_COLOR.to_string(),
+        clicolor: ENV_CLICOLOR.to_string(),
     }
 }
 
 /// Apply the environment contract to a command (clears existing env first).
+///
+/// Clearing also drops `FORCE_COLOR` and similar overrides from the caller.
 pub(crate) fn apply_env_contract(command: &mut Command) {
     command.env_clear();
     command.env("LC_ALL", ENV_LC_ALL);
     command.env("TZ", ENV_TZ);
     command.env("TERM", ENV_TERM);
+    command.env("NO_COLOR", ENV_NO_COLOR);
+    command.env("CLICOLOR", ENV_CLICOLOR);
     command.env("PATH", ENV_PATH);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_contract_sets_color_vars_and_drops_force_color() {
+        let mut command = Command::new("env");
+        command.env("FORCE_COLOR", "1").env("CLICOLOR_FORCE", "1");
+        apply_env_contract(&mut command);
+        let output = command.output().expect("run env");
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let vars: Vec<&str> = stdout.lines().collect();
+        assert!(vars.contains(&"NO_COLOR=1"), "{stdout}");
+        assert!(vars.contains(&"CLICOLOR=0"), "{stdout}");
+        assert!(!stdout.contains("FORCE_COLOR"), "{stdout}");
+
+        let contract = env_contract();
+        assert_eq!(contract.no_color, "1");
+        assert_eq!(contract.clicolor, "0");
+    }
+}
@@ -0,0 +1,148 @@
+//! Runtime prerequisite checks for `bman doctor`.
+
+use std::env;
+use std::path::Path;
+
+use crate::fixture::load_fixture_catalog;
+use crate::lm::{
+    example_scenario_path, fixture_catalog_path, lm_schema_path, load_lm_command,
+    scenario_schema_path, LmCommand,
+};
+use crate::runner::{find_in_path, SandboxKind};
+
+/// Outcome of a single prerequisite check.
+pub(crate) enum DoctorStatus {
+    Pass(String),
+    /// Not required for the requested mode, but worth knowing.
+    Warn(String),
+    /// Required and missing; `hint` says how to fix it.
+    Fail { detail: String, hint: String },
+}
+
+/// A named prerequisite check and its outcome.
+pub(crate) struct DoctorCheck {
+    pub(crate) name: &'static str,
+    pub(crate) status: DoctorStatus,
+}
+
+/// Check the sandbox, LM backend, and repo assets a run depends on.
+///
+/// With `direct`, a missing sandbox is only a warning.
+pub(crate) fn run_checks(repo_root: &Path, direct: bool) -> Vec<DoctorCheck> {
+    let mut checks = vec![check_sandbox(direct), check_lm_command()];
+    for (name, path) in [
+        ("scenario schema", scenario_schema_path(repo_root)),
+        ("lm schema", lm_schema_path(repo_root)),
+    ] {
+        let status = if path.is_file() {
+            DoctorStatus::Pass(path.display().to_string())
+        } else {
+            DoctorStatus::Fail {
+                detail: format!("{} not found", path.display()),
+                hint: "run bman from the repository root".to_string(),
+            }
+        };
+        checks.push(DoctorCheck { name, status });
+    }
+    let catalog_path = fixture_catalog_path(repo_root);
+    let fixtures_root = catalog_path.parent().unwrap_or(repo_root);
+    checks.push(DoctorCheck {
+        name: "fixture catalog",
+        status: match load_fixture_catalog(fixtures_root) {
+            Ok(ids) => DoctorStatus::Pass(format!("{} fixture(s)", ids.len())),
+            Err(err) => DoctorStatus::Fail {
+                detail: format!("{err:#}"),
+                hint: "run bman from the repository root and check fixtures/".to_string(),
+            },
+        },
+    });
+    let example_path = example_scenario_path(repo_root);
+    checks.push(DoctorCheck {
+        name: "example scenario",
+        status: if example_path.is_file() {
+            DoctorStatus::Pass(example_path.display().to_string())
+        } else {
+            DoctorStatus::Warn(format!(
+                "{} not found; prompts omit the format example",
+                example_path.display()
+            ))
+        },
+    });
+    checks
+}
+
+fn check_sandbox(direct: bool) -> DoctorCheck {
+    let missing = |detail: String, hint: &str| {
+        if direct {
+            DoctorStatus::Warn(format!("{detail} (not needed with --direct)"))
+        } else {
+            DoctorStatus::Fail {
+                detail,
+                hint: hint.to_string(),
+            }
+        }
+    };
+    let status = match SandboxKind::host() {
+        Some(SandboxKind::Bwrap) => match find_in_path("bwrap") {
+            None => missing(
+                "bwrap not found in PATH".to_string(),
+                "install bubblewrap, or rerun with --direct (unsandboxed)",
+            ),
+            Some(_) if !Path::new("/nix/store").exists() => missing(
+                "/nix/store not found; the bwrap sandbox mounts it read-only".to_string(),
+                "install Nix, or rerun with --direct (unsandboxed)",
+            ),
+            Some(path) => DoctorStatus::Pass(format!("bwrap at {}", path.display())),
+        },
+        Some(SandboxKind::SandboxExec) => match find_in_path("sandbox-exec") {
+            Some(path) => DoctorStatus::Pass(format!("sandbox-exec at {}", path.display())),
+            None => missing(
+                "sandbox-exec not found in PATH".to_string(),
+                "rerun with --direct (unsandboxed)",
+            ),
+        },
+        None => missing(
+            format!("no sandbox supported on {}", env::consts::OS),
+            "rerun with --direct (unsandboxed)",
+        ),
+    };
+    DoctorCheck {
+        name: "sandbox",
+        status,
+    }
+}
+
+fn check_lm_command() -> DoctorCheck {
+    let status = match load_lm_command() {
+        Err(err) => DoctorStatus::Fail {
+            detail: format!("{err:#}"),
+            hint: "fix BMAN_LM_COMMAND (JSON with \"command\" or \"http\")".to_string(),
+        },
+        Ok(LmCommand::Argv(argv)) => {
+            let program = argv.first().map(String::as_str).unwrap_or("");
+            let found = if program.contains('/') {
+                Path::new(program).is_file().then(|| program.into())
+            } else {
+                find_in_path(program)
+            };
+            match found {
+                Some(path) => DoctorStatus::Pass(format!("command {}", path.display())),
+                None => DoctorStatus::Fail {
+                    detail: format!("LM program {program:?} not found"),
+                    hint: "install it, or set BMAN_LM_COMMAND to another backend".to_string(),
+                },
+            }
+        }
+        Ok(LmCommand::Http(config)) => match &config.api_key_env {
+            Some(var) if env::var_os(var).is_none() => DoctorStatus::Fail {
+                detail: format!("api_key_env {var} is not set"),
+                hint: format!("export {var} with the endpoint's API key"),
+            },
+            _ => DoctorStatus::Pass(LmCommand::Http(config).describe()),
+        },
+    };
+    DoctorCheck {
+        name: "lm command",
+        status,
+    }
+}
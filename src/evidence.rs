@@ -1,12 +1,17 @@
 //! Evidence bundle metadata and output helpers.
 
 use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
 use serde::Serialize;
-use std::fs;
+use std::fs::{self, File};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::contract::EnvContract;
+use crate::lm::HelpEncoding;
+use crate::runner::RoBind;
 use crate::scenario::ScenarioLimits;
 
 /// Tool version emitted in evidence metadata.
@@ -44,6 +49,10 @@ pub(crate) struct BinaryMeta {
     pub(crate) path: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) size_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) mtime: Option<i64>,
 }
 
 /// Fixture identity recorded in metadata.
@@ -52,6 +61,12 @@ pub(crate) struct FixtureMeta {
     pub(crate) id: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub(crate) sha256: Option<String>,
+    /// Whether the run left the fixture differing from its manifest.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) mutated: Option<bool>,
+    /// Fixture-relative paths added, removed, or modified by the run.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) changed_paths: Vec<String>,
 }
 
 /// Execution outcome details for a completed run.
@@ -60,6 +75,16 @@ pub(crate) struct ResultMeta {
     pub(crate) exit_code: Option<i32>,
     pub(crate) timed_out: bool,
     pub(crate) wall_time_ms: u64,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub(crate) spawn_retries: u32,
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn is_zero(value: &u32) -> bool {
+    *value == 0
 }
 
 /// Artifact hashes and sizes recorded in metadata.
@@ -69,12 +94,32 @@ pub(crate) struct ArtifactsMeta {
     pub(crate) stderr_sha256: String,
     pub(crate) stdout_bytes: u64,
     pub(crate) stderr_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) combined_sha256: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) combined_bytes: Option<u64>,
+    /// Output files are stored gzipped with a `.gz` suffix; hashes and sizes
+    /// above always describe the uncompressed bytes.
+    #[serde(skip_serializing_if = "is_false")]
+    pub(crate) compressed: bool,
 }
 
 /// Sandbox mode indicator recorded in metadata.
 #[derive(Serialize)]
 pub(crate) struct SandboxMeta {
     pub(crate) mode: String,
+    /// Verified hash of the binary copy executed inside the sandbox.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) binary_sha256: Option<String>,
+    /// Args from `--preamble` passed before the scenario args.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) preamble: Vec<String>,
+    /// Extra read-only mounts from `--ro-bind`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) ro_binds: Vec<RoBind>,
+    /// Set when `--no-limits` skipped the scenario rlimits.
+    #[serde(skip_serializing_if = "is_false")]
+    pub(crate) no_limits: bool,
 }
 
 /// Error report recorded when execution fails early.
@@ -99,6 +144,31 @@ pub(crate) enum Outcome {
     Exited,
 }
 
+/// Help capture details written to `help.json`.
+#[derive(Serialize)]
+pub(crate) struct HelpMeta {
+    /// Help invocation that produced the text (`--help`, `-h`, `--help-all`,
+    /// the usage probe, or space-joined `--help-args`).
+    pub(crate) flag: String,
+    /// Stream the text was taken from (`stdout` or `stderr`).
+    pub(crate) stream: &'static str,
+    /// Args from `--preamble` passed before the help flag.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) preamble: Vec<String>,
+    /// `--help-cwd` directory, or `temp` for a fresh empty directory.
+    pub(crate) cwd: String,
+    pub(crate) bytes: u64,
+    pub(crate) sha256: String,
+    pub(crate) encoding: HelpEncoding,
+    #[serde(skip_serializing_if = "is_false")]
+    pub(crate) truncated: bool,
+    #[serde(skip_serializing_if = "is_false")]
+    pub(crate) timed_out: bool,
+    /// Unknown-option token tried for a usage error when help listed no options.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) usage_probe: Option<String>,
+}
+
 /// Evidence directory name used under the output root.
 pub(crate) const EVIDENCE_DIR: &str = "evidence";
 
@@ -167,3 +237,43 @@ pub(crate) fn write_meta(path: &Path, meta: Meta) -> Result<()> {
     fs::write(path.join("meta.json"), json).context("write meta.json")?;
     Ok(())
 }
+
+/// Write an output artifact, gzipped as `<name>.gz` when `compress` is set.
+pub(crate) fn write_artifact(dir: &Path, name: &str, bytes: &[u8], compress: bool) -> Result<()> {
+    if !compress {
+        return fs::write(dir.join(name), bytes).with_context(|| format!("write {name}"));
+    }
+    let gz_name = format!("{name}.gz");
+    let file = File::create(dir.join(&gz_name)).with_context(|| format!("create {gz_name}"))?;
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    encoder
+        .write_all(bytes)
+        .and_then(|()| encoder.finish().map(drop))
+        .with_context(|| format!("write {gz_name}"))
+}
+
+/// Serialize and write `help.json` into the evidence directory.
+pub(crate) fn write_help_meta(path: &Path, help: &HelpMeta) -> Result<()> {
+    let json = serde_json::to_vec_pretty(help).context("serialize help.json")?;
+    fs::write(path.join("help.json"), json).context("write help.json")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hashing::sha256_hex;
+    use crate::verify::read_gz;
+
+    #[test]
+    fn compressed_artifact_round_trips_through_verify() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let bytes: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+        write_artifact(dir.path(), "stdout.txt", &bytes, true).unwrap();
+        assert!(!dir.path().join("stdout.txt").exists());
+
+        let restored = read_gz(&dir.path().join("stdout.txt.gz")).unwrap();
+        assert_eq!(restored.len(), bytes.len());
+        assert_eq!(sha256_hex(&restored), sha256_hex(&bytes));
+    }
+}
@@ -1,12 +1,14 @@
 //! Fixture verification and deterministic materialization.
 
 use anyhow::{anyhow, Context, Result};
+use base64::prelude::{Engine as _, BASE64_STANDARD};
 use filetime::FileTime;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::fs;
+use std::fs::{self, File};
+use std::io;
 use std::os::unix::fs::PermissionsExt;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use tempfile::TempDir;
 use walkdir::WalkDir;
 
@@ -43,12 +45,24 @@ pub(crate) struct FixtureEntry {
     #[serde(default)]
     pub(crate) sha256: Option<String>,
     pub(crate) mtime: i64,
+    /// Extended attributes applied after materialization (opt-in).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) xattrs: Vec<FixtureXattr>,
+}
+
+/// A single extended attribute on a fixture entry.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct FixtureXattr {
+    pub(crate) name: String,
+    pub(crate) value_base64: String,
 }
 
 /// A fixture materialized into a temporary run root.
 pub(crate) struct PreparedFixture {
     pub(crate) fixture_root: PathBuf,
     pub(crate) fixture_hash: String,
+    manifest: FixtureManifest,
     _temp_dir: TempDir,
 }
 
@@ -59,6 +73,14 @@ pub(crate) struct FixtureError {
     pub(crate) is_missing: bool,
 }
 
+/// Where a fixture's contents are stored.
+enum FixtureTree {
+    /// A `tree/` directory checked into the repo.
+    Dir(PathBuf),
+    /// A `tree.tar` archive extracted at run time.
+    Archive(PathBuf),
+}
+
 #[derive(Copy, Clone, PartialEq, Eq)]
 enum EntryKind {
     File,
@@ -71,13 +93,18 @@ pub(crate) fn fixture_root(root: &Path, fixture_id: &str) -> Result<PathBuf> {
     Ok(root.join(fixture_id))
 }
 
-/// Load the fixture catalog and return the allowed fixture IDs.
-pub(crate) fn load_fixture_catalog(fixtures_root: &Path) -> Result<HashSet<String>> {
+/// Read catalog entries in file order without checking them.
+pub(crate) fn read_fixture_catalog(fixtures_root: &Path) -> Result<Vec<FixtureCatalogEntry>> {
     let catalog_path = fixtures_root.join("catalog.json");
     let bytes = fs::read(&catalog_path)
         .with_context(|| format!("read fixture catalog {}", catalog_path.display()))?;
-    let entries: Vec<FixtureCatalogEntry> = serde_json::from_slice(&bytes)
-        .with_context(|| format!("parse fixture catalog {}", catalog_path.display()))?;
+    serde_json::from_slice(&bytes)
+        .with_context(|| format!("parse fixture catalog {}", catalog_path.display()))
+}
+
+/// Load the fixture catalog and return the allowed fixture IDs.
+pub(crate) fn load_fixture_catalog(fixtures_root: &Path) -> Result<HashSet<String>> {
+    let entries = read_fixture_catalog(fixtures_root)?;
 
     let mut ids = HashSet::new();
     for entry in entries {
@@ -100,10 +127,9 @@ pub(crate) fn load_fixture_catalog(fixtures_root: &Path) -> Result<HashSet<Strin
             ));
         }
         let manifest_path = fixture_dir.join("manifest.json");
-        let tree_path = fixture_dir.join("tree");
-        if !manifest_path.is_file() || !tree_path.is_dir() {
+        if !manifest_path.is_file() || locate_tree(&fixture_dir).is_none() {
             return Err(anyhow!(
-                "fixture catalog entry missing manifest.json or tree/: {}",
+                "fixture catalog entry missing manifest.json or tree/ (or tree.tar): {}",
                 entry.id
             ));
         }
@@ -121,14 +147,16 @@ pub(crate) fn prepare_fixture(fixture_dir: &Path) -> Result<PreparedFixture, Fix
         });
     }
     let manifest_path = fixture_dir.join("manifest.json");
-    let tree_path = fixture_dir.join("tree");
-    if !manifest_path.exists() || !tree_path.exists() {
-        return Err(FixtureError {
-            message: "fixture missing manifest.json or tree/".to_string(),
-            details: Vec::new(),
-            is_missing: true,
-        });
-    }
+    let tree = match locate_tree(fixture_dir) {
+        Some(tree) if manifest_path.exists() => tree,
+        _ => {
+            return Err(FixtureError {
+                message: "fixture missing manifest.json or tree/ (or tree.tar)".to_string(),
+                details: Vec::new(),
+                is_missing: true,
+            });
+        }
+    };
 
     let manifest = load_manifest(&manifest_path).map_err(|err| FixtureError {
         message: "fixture manifest invalid".to_string(),
@@ -140,11 +168,13 @@ pub(crate) fn prepare_fixture(fixture_dir: &Path) -> Result<PreparedFixture, Fix
         details: vec![err.to_string()],
         is_missing: false,
     })?;
-    verify_fixture_tree(&tree_path, &manifest, false).map_err(|err| FixtureError {
-        message: "fixture tree failed validation".to_string(),
-        details: vec![err.to_string()],
-        is_missing: false,
-    })?;
+    if let FixtureTree::Dir(tree_path) = &tree {
+        verify_fixture_tree(tree_path, &manifest, false).map_err(|err| FixtureError {
+            message: "fixture tree failed validation".to_string(),
+            details: vec![err.to_string()],
+            is_missing: false,
+        })?;
+    }
 
     let fixture_hash = canonical_manifest_hash(&manifest).map_err(|err| FixtureError {
         message: "fixture manifest hashing failed".to_string(),
@@ -163,11 +193,27 @@ pub(crate) fn prepare_fixture(fixture_dir: &Path) -> Result<PreparedFixture, Fix
         details: vec![err.to_string()],
         is_missing: false,
     })?;
-    copy_tree(&tree_path, &fixture_root).map_err(|err| FixtureError {
-        message: "failed to copy fixture tree".to_string(),
-        details: vec![err.to_string()],
-        is_missing: false,
-    })?;
+    match &tree {
+        FixtureTree::Dir(tree_path) => {
+            copy_tree(tree_path, &fixture_root).map_err(|err| FixtureError {
+                message: "failed to copy fixture tree".to_string(),
+                details: vec![err.to_string()],
+                is_missing: false,
+            })?;
+        }
+        FixtureTree::Archive(archive_path) => {
+            extract_archive(archive_path, &fixture_root).map_err(|err| FixtureError {
+                message: "failed to extract fixture archive".to_string(),
+                details: vec![err.to_string()],
+                is_missing: false,
+            })?;
+            verify_fixture_tree(&fixture_root, &manifest, false).map_err(|err| FixtureError {
+                message: "fixture tree failed validation".to_string(),
+                details: vec![err.to_string()],
+                is_missing: false,
+            })?;
+        }
+    }
     apply_manifest(&fixture_root, &manifest).map_err(|err| FixtureError {
         message: "failed to apply fixture manifest".to_string(),
         details: vec![err.to_string()],
@@ -183,9 +229,73 @@ pub(crate) fn prepare_fixture(fixture_dir: &Path) -> Result<PreparedFixture, Fix
         _temp_dir: temp_dir,
         fixture_root,
         fixture_hash,
+        manifest,
     })
 }
 
+impl PreparedFixture {
+    /// Paths that no longer match the manifest after a run, sorted.
+    ///
+    /// Covers added, removed, and retyped entries, file content, and mode or
+    /// mtime changes; xattrs are not rechecked.
+    pub(crate) fn changed_paths(&self) -> Result<Vec<String>> {
+        let root = &self.fixture_root;
+        let (expected, _) = manifest_entries(&self.manifest)?;
+        let mut changed = HashSet::new();
+        let mut seen = HashSet::new();
+        for entry in WalkDir::new(root).min_depth(1) {
+            let entry = entry?;
+            let rel = entry.path().strip_prefix(root)?.to_path_buf();
+            seen.insert(rel.clone());
+            let Some(manifest_entry) = expected.get(&rel) else {
+                changed.insert(rel);
+                continue;
+            };
+            let file_type = entry.file_type();
+            let kind_matches = match manifest_entry.entry_type.as_str() {
+                "file" => file_type.is_file(),
+                "dir" => file_type.is_dir(),
+                _ => false,
+            };
+            if !kind_matches || !entry_unchanged(entry.path(), manifest_entry)? {
+                changed.insert(rel);
+            }
+        }
+        for path in expected.keys() {
+            if !seen.contains(path) {
+                changed.insert(path.clone());
+            }
+        }
+        let mut paths: Vec<String> = changed
+            .into_iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        paths.sort();
+        Ok(paths)
+    }
+}
+
+/// Whether a materialized entry still has its manifest content, mode, and mtime.
+fn entry_unchanged(target: &Path, entry: &FixtureEntry) -> Result<bool> {
+    let metadata =
+        fs::symlink_metadata(target).with_context(|| format!("stat {}", target.display()))?;
+    if metadata.permissions().mode() & 0o7777 != parse_mode(&entry.mode)? {
+        return Ok(false);
+    }
+    if FileTime::from_last_modification_time(&metadata).unix_seconds() != entry.mtime {
+        return Ok(false);
+    }
+    if entry.entry_type == "file" {
+        if entry.size.is_some_and(|size| size != metadata.len()) {
+            return Ok(false);
+        }
+        if let Some(expected_hash) = &entry.sha256 {
+            return Ok(sha256_file(target)? == *expected_hash);
+        }
+    }
+    Ok(true)
+}
+
 /// Validate a fixture on disk without materializing it.
 pub(crate) fn validate_fixture(fixture_dir: &Path) -> Result<String> {
     if !fixture_dir.exists() {
@@ -195,17 +305,40 @@ pub(crate) fn validate_fixture(fixture_dir: &Path) -> Result<String> {
         ));
     }
     let manifest_path = fixture_dir.join("manifest.json");
-    let tree_path = fixture_dir.join("tree");
-    if !manifest_path.exists() || !tree_path.exists() {
-        return Err(anyhow!("fixture missing manifest.json or tree/"));
-    }
+    let tree = match locate_tree(fixture_dir) {
+        Some(tree) if manifest_path.exists() => tree,
+        _ => return Err(anyhow!("fixture missing manifest.json or tree/ (or tree.tar)")),
+    };
 
     let manifest = load_manifest(&manifest_path).context("load fixture manifest")?;
     validate_manifest(&manifest).context("validate fixture manifest")?;
-    verify_fixture_tree(&tree_path, &manifest, false).context("verify fixture tree")?;
+    match tree {
+        FixtureTree::Dir(tree_path) => {
+            verify_fixture_tree(&tree_path, &manifest, false).context("verify fixture tree")?;
+        }
+        FixtureTree::Archive(archive_path) => {
+            let temp_dir = TempDir::new().context("create temp dir")?;
+            extract_archive(&archive_path, temp_dir.path()).context("extract fixture archive")?;
+            verify_fixture_tree(temp_dir.path(), &manifest, false)
+                .context("verify fixture archive")?;
+        }
+    }
     canonical_manifest_hash(&manifest).context("hash fixture manifest")
 }
 
+/// Find the fixture contents, preferring `tree/` over `tree.tar`.
+fn locate_tree(fixture_dir: &Path) -> Option<FixtureTree> {
+    let tree_path = fixture_dir.join("tree");
+    if tree_path.is_dir() {
+        return Some(FixtureTree::Dir(tree_path));
+    }
+    let archive_path = fixture_dir.join("tree.tar");
+    if archive_path.is_file() {
+        return Some(FixtureTree::Archive(archive_path));
+    }
+    None
+}
+
 fn load_manifest(path: &Path) -> Result<FixtureManifest> {
     let bytes = fs::read(path).with_context(|| format!("read manifest {}", path.display()))?;
     let manifest: FixtureManifest =
@@ -234,6 +367,16 @@ fn validate_manifest(manifest: &FixtureManifest) -> Result<()> {
             }
         };
         parse_mode(&entry.mode)?;
+        let mut names = HashSet::new();
+        for attr in &entry.xattrs {
+            if attr.name.is_empty() || attr.name.contains('\0') {
+                return Err(anyhow!("invalid xattr name on {}", entry.path));
+            }
+            if !names.insert(attr.name.as_str()) {
+                return Err(anyhow!("duplicate xattr {} on {}", attr.name, entry.path));
+            }
+            decode_xattr(attr).with_context(|| format!("xattr on {}", entry.path))?;
+        }
         if entry.mtime < 0 {
             return Err(anyhow!("mtime must be >= 0"));
         }
@@ -288,8 +431,61 @@ fn copy_tree(src: &Path, dst: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Extract a fixture archive, accepting only regular files and directories.
+///
+/// Leading `./` components are ignored so archives built with
+/// `tar -cf tree.tar -C tree .` work. Permissions and mtimes come from the
+/// manifest, not the archive headers.
+fn extract_archive(archive_path: &Path, dst: &Path) -> Result<()> {
+    let file =
+        File::open(archive_path).with_context(|| format!("open {}", archive_path.display()))?;
+    let mut archive = tar::Archive::new(file);
+    for entry in archive.entries().context("read fixture archive")? {
+        let mut entry = entry.context("read fixture archive entry")?;
+        let raw_path = entry.path().context("decode archive entry path")?.into_owned();
+        let rel: PathBuf = raw_path
+            .components()
+            .filter(|comp| *comp != Component::CurDir)
+            .collect();
+        if rel.as_os_str().is_empty() {
+            continue;
+        }
+        let rel_str = rel
+            .to_str()
+            .ok_or_else(|| anyhow!("non-UTF-8 archive entry {}", raw_path.display()))?;
+        validate_relative_path(rel_str)
+            .map_err(|err| anyhow!("archive entry {}: {err}", raw_path.display()))?;
+        let dest_path = dst.join(&rel);
+        match entry.header().entry_type() {
+            tar::EntryType::Directory => fs::create_dir_all(&dest_path)?,
+            tar::EntryType::Regular => {
+                if let Some(parent) = dest_path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut out = File::create(&dest_path)
+                    .with_context(|| format!("create {}", dest_path.display()))?;
+                io::copy(&mut entry, &mut out)
+                    .with_context(|| format!("write {}", dest_path.display()))?;
+            }
+            other => {
+                return Err(anyhow!(
+                    "unsupported archive entry {} ({other:?}); only files and directories",
+                    raw_path.display()
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply manifest modes and mtimes, deepest entries first.
+///
+/// Children are handled before their parent so a directory's mtime and
+/// (possibly restrictive) mode are set only after its contents are final.
 fn apply_manifest(root: &Path, manifest: &FixtureManifest) -> Result<()> {
-    for entry in &manifest.entries {
+    let mut entries: Vec<&FixtureEntry> = manifest.entries.iter().collect();
+    entries.sort_by_key(|entry| std::cmp::Reverse(Path::new(&entry.path).components().count()));
+    for entry in entries {
         let target = root.join(&entry.path);
         let mode = parse_mode(&entry.mode)?;
         let mtime = FileTime::from_unix_time(entry.mtime, 0);
@@ -301,6 +497,15 @@ fn apply_manifest(root: &Path, manifest: &FixtureManifest) -> Result<()> {
         if entry.entry_type == "dir" && !metadata.is_dir() {
             return Err(anyhow!("expected dir for {}", entry.path));
         }
+        for attr in &entry.xattrs {
+            xattr::set(&target, &attr.name, &decode_xattr(attr)?).with_context(|| {
+                format!(
+                    "set xattr {} on {} (filesystem may not support xattrs)",
+                    attr.name,
+                    target.display()
+                )
+            })?;
+        }
         fs::set_permissions(&target, fs::Permissions::from_mode(mode))
             .with_context(|| format!("set permissions {}", target.display()))?;
         filetime::set_file_times(&target, mtime, mtime)
@@ -360,6 +565,13 @@ fn verify_fixture_tree(
             if mtime != entry.mtime {
                 return Err(anyhow!("mtime mismatch for {}", path.display()));
             }
+            for attr in &entry.xattrs {
+                let actual = xattr::get(&target, &attr.name)
+                    .with_context(|| format!("read xattr {} on {}", attr.name, path.display()))?;
+                if actual != Some(decode_xattr(attr)?) {
+                    return Err(anyhow!("xattr {} mismatch for {}", attr.name, path.display()));
+                }
+            }
         }
     }
     Ok(())
@@ -409,6 +621,238 @@ fn manifest_entries(
     Ok((entries, kinds))
 }
 
+fn decode_xattr(attr: &FixtureXattr) -> Result<Vec<u8>> {
+    BASE64_STANDARD
+        .decode(&attr.value_base64)
+        .map_err(|err| anyhow!("invalid base64 for xattr {}: {err}", attr.name))
+}
+
 fn parse_mode(value: &str) -> Result<u32> {
     u32::from_str_radix(value, 8).map_err(|_| anyhow!("invalid mode {value}"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Append a tar entry with a raw name, bypassing the builder's path checks.
+    fn append_raw(
+        builder: &mut tar::Builder<Vec<u8>>,
+        name: &str,
+        entry_type: tar::EntryType,
+        data: &[u8],
+        link: Option<&str>,
+    ) {
+        let mut header = tar::Header::new_gnu();
+        header.as_old_mut().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_entry_type(entry_type);
+        header.set_mode(if entry_type.is_dir() { 0o755 } else { 0o644 });
+        header.set_size(data.len() as u64);
+        if let Some(link) = link {
+            header.set_link_name(link).unwrap();
+        }
+        header.set_cksum();
+        builder.append(&header, data).unwrap();
+    }
+
+    /// Write an in-memory archive to `dir/tree.tar` and return its path.
+    fn write_archive(dir: &Path, build: impl FnOnce(&mut tar::Builder<Vec<u8>>)) -> PathBuf {
+        let mut builder = tar::Builder::new(Vec::new());
+        build(&mut builder);
+        let path = dir.join("tree.tar");
+        fs::write(&path, builder.into_inner().unwrap()).unwrap();
+        path
+    }
+
+    fn extract_error(build: impl FnOnce(&mut tar::Builder<Vec<u8>>)) -> String {
+        let dir = TempDir::new().unwrap();
+        let archive = write_archive(dir.path(), build);
+        let dst = dir.path().join("out");
+        fs::create_dir(&dst).unwrap();
+        let err = extract_archive(&archive, &dst).expect_err("archive should be rejected");
+        format!("{err:#}")
+    }
+
+    #[test]
+    fn extract_archive_rejects_parent_dir_entry() {
+        let err = extract_error(|b| append_raw(b, "../x", tar::EntryType::Regular, b"x", None));
+        assert!(err.contains("../x"), "{err}");
+        assert!(err.contains("invalid component"), "{err}");
+    }
+
+    #[test]
+    fn extract_archive_rejects_absolute_entry() {
+        let err = extract_error(|b| append_raw(b, "/abs", tar::EntryType::Regular, b"x", None));
+        assert!(err.contains("/abs"), "{err}");
+    }
+
+    #[test]
+    fn extract_archive_rejects_symlink_entry() {
+        let err = extract_error(|b| {
+            append_raw(b, "link", tar::EntryType::Symlink, b"", Some("/etc/passwd"))
+        });
+        assert!(err.contains("unsupported archive entry link"), "{err}");
+    }
+
+    #[test]
+    fn extract_archive_rejects_hardlink_entry() {
+        let err = extract_error(|b| {
+            append_raw(b, "a", tar::EntryType::Regular, b"a", None);
+            append_raw(b, "b", tar::EntryType::Link, b"", Some("a"));
+        });
+        assert!(err.contains("unsupported archive entry b"), "{err}");
+    }
+
+    fn mtime_of(path: &Path) -> i64 {
+        FileTime::from_last_modification_time(&fs::metadata(path).unwrap()).unix_seconds()
+    }
+
+    /// Manifest entry for a regular file with mode 0644.
+    fn file_entry(path: &str, data: &[u8], mtime: i64) -> serde_json::Value {
+        serde_json::json!({
+            "path": path,
+            "type": "file",
+            "mode": "0644",
+            "size": data.len(),
+            "sha256": sha256_hex(data),
+            "mtime": mtime
+        })
+    }
+
+    /// Write a version 1 `manifest.json` listing `entries` into `dir`.
+    fn write_manifest(dir: &Path, entries: serde_json::Value) {
+        let manifest = serde_json::json!({
+            "version": 1,
+            "description": "test fixture",
+            "entries": entries
+        });
+        fs::write(dir.join("manifest.json"), manifest.to_string()).unwrap();
+    }
+
+    fn prepare_ok(dir: &Path) -> PreparedFixture {
+        prepare_fixture(dir).unwrap_or_else(|err| {
+            panic!("{}: {:?}", err.message, err.details);
+        })
+    }
+
+    #[test]
+    fn prepare_fixture_sets_directory_mtimes_after_children() {
+        let dir = TempDir::new().unwrap();
+        let tree = dir.path().join("tree");
+        fs::create_dir_all(tree.join("a/b")).unwrap();
+        fs::write(tree.join("a/one.txt"), b"1").unwrap();
+        fs::write(tree.join("a/b/two.txt"), b"22").unwrap();
+        write_manifest(
+            dir.path(),
+            serde_json::json!([
+                { "path": "a", "type": "dir", "mode": "0755", "mtime": 1_500_000_000 },
+                file_entry("a/one.txt", b"1", 1_600_000_000),
+                { "path": "a/b", "type": "dir", "mode": "0755", "mtime": 1_550_000_000 },
+                file_entry("a/b/two.txt", b"22", 1_650_000_000)
+            ]),
+        );
+
+        let prepared = prepare_ok(dir.path());
+        let root = &prepared.fixture_root;
+        assert_eq!(mtime_of(&root.join("a")), 1_500_000_000);
+        assert_eq!(mtime_of(&root.join("a/b")), 1_550_000_000);
+        assert_eq!(mtime_of(&root.join("a/b/two.txt")), 1_650_000_000);
+        verify_fixture_tree(root, &prepared.manifest, true).unwrap();
+    }
+
+    #[test]
+    fn prepare_fixture_round_trips_xattrs() {
+        if !xattr::SUPPORTED_PLATFORM {
+            return;
+        }
+        let dir = TempDir::new().unwrap();
+        let tree = dir.path().join("tree");
+        fs::create_dir(&tree).unwrap();
+        fs::write(tree.join("labelled"), b"x").unwrap();
+        // Skip on filesystems without user xattrs (e.g. some tmpfs setups).
+        if let Err(err) = xattr::set(tree.join("labelled"), "user.bman_probe", b"1") {
+            if err.raw_os_error() == Some(libc::ENOTSUP) {
+                return;
+            }
+            panic!("probe xattr: {err}");
+        }
+        xattr::remove(tree.join("labelled"), "user.bman_probe").unwrap();
+        let mut entry = file_entry("labelled", b"x", 1_600_000_000);
+        entry["xattrs"] = serde_json::json!([{
+            "name": "user.label",
+            "value_base64": BASE64_STANDARD.encode("hello")
+        }]);
+        write_manifest(dir.path(), serde_json::json!([entry]));
+
+        let prepared = prepare_ok(dir.path());
+        let value = xattr::get(prepared.fixture_root.join("labelled"), "user.label").unwrap();
+        assert_eq!(value.as_deref(), Some(&b"hello"[..]));
+        verify_fixture_tree(&prepared.fixture_root, &prepared.manifest, true).unwrap();
+    }
+
+    #[test]
+    fn prepare_fixture_materializes_valid_archive() {
+        let dir = TempDir::new().unwrap();
+        write_archive(dir.path(), |b| {
+            append_raw(b, "./", tar::EntryType::Directory, b"", None);
+            append_raw(b, "./sub/", tar::EntryType::Directory, b"", None);
+            append_raw(b, "./sub/a.txt", tar::EntryType::Regular, b"hello\n", None);
+        });
+        let mut file = file_entry("sub/a.txt", b"hello\n", 1_600_000_000);
+        file["mode"] = "0600".into();
+        write_manifest(
+            dir.path(),
+            serde_json::json!([
+                { "path": "sub", "type": "dir", "mode": "0750", "mtime": 1_700_000_000 },
+                file
+            ]),
+        );
+
+        let prepared = prepare_ok(dir.path());
+        let file = prepared.fixture_root.join("sub/a.txt");
+        assert_eq!(fs::read(&file).unwrap(), b"hello\n");
+        let mode = fs::metadata(&file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o7777, 0o600);
+        assert_eq!(mtime_of(&file), 1_600_000_000);
+        assert_eq!(prepared.changed_paths().unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn changed_paths_reports_each_mutation() {
+        let dir = TempDir::new().unwrap();
+        let tree = dir.path().join("tree");
+        fs::create_dir_all(tree.join("d")).unwrap();
+        for (path, data) in [
+            ("keep.txt", b"keep"),
+            ("mode.txt", b"mode"),
+            ("d/gone.txt", b"gone"),
+            ("d/edit.txt", b"edit"),
+        ] {
+            fs::write(tree.join(path), data).unwrap();
+        }
+        write_manifest(
+            dir.path(),
+            serde_json::json!([
+                file_entry("keep.txt", b"keep", 1_600_000_000),
+                file_entry("mode.txt", b"mode", 1_600_000_000),
+                { "path": "d", "type": "dir", "mode": "0755", "mtime": 1_500_000_000 },
+                file_entry("d/gone.txt", b"gone", 1_600_000_000),
+                file_entry("d/edit.txt", b"edit", 1_600_000_000)
+            ]),
+        );
+        let prepared = prepare_ok(dir.path());
+        assert_eq!(prepared.changed_paths().unwrap(), Vec::<String>::new());
+
+        let root = &prepared.fixture_root;
+        fs::write(root.join("new.txt"), b"new").unwrap();
+        fs::remove_file(root.join("d/gone.txt")).unwrap();
+        fs::write(root.join("d/edit.txt"), b"EDIT").unwrap();
+        fs::set_permissions(root.join("mode.txt"), fs::Permissions::from_mode(0o600)).unwrap();
+
+        // Removing d/gone.txt also bumps d's mtime.
+        assert_eq!(
+            prepared.changed_paths().unwrap(),
+            ["d", "d/edit.txt", "d/gone.txt", "mode.txt", "new.txt"]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Evidence retention: prune old run directories under the evidence root.
+
+use anyhow::{anyhow, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::evidence::EVIDENCE_DIR;
+
+/// Retention policy applied to evidence run directories.
+pub(crate) struct GcPolicy {
+    pub(crate) keep_last: Option<usize>,
+    pub(crate) older_than: Option<Duration>,
+    pub(crate) dry_run: bool,
+}
+
+/// A run directory discovered under the evidence root.
+struct RunDir {
+    path: PathBuf,
+    modified: SystemTime,
+}
+
+/// Prune evidence run directories according to the policy.
+///
+/// Only direct child directories of `<out_dir>/evidence` are considered, and
+/// the newest `keep_last` runs are never removed. Returns the removed (or, in
+/// dry-run mode, the would-be removed) paths.
+pub(crate) fn gc_evidence(out_dir: &Path, policy: &GcPolicy) -> Result<Vec<PathBuf>> {
+    if policy.keep_last.is_none() && policy.older_than.is_none() {
+        return Err(anyhow!("specify --keep-last and/or --older-than"));
+    }
+    let root = evidence_root(out_dir)?;
+    let mut runs = scan_runs(&root)?;
+    runs.sort_by(|a, b| b.modified.cmp(&a.modified).then_with(|| b.path.cmp(&a.path)));
+
+    let keep = policy.keep_last.unwrap_or(0);
+    let now = SystemTime::now();
+    let mut removed = Vec::new();
+    for run in runs.into_iter().skip(keep) {
+        if let Some(min_age) = policy.older_than {
+            let age = now.duration_since(run.modified).unwrap_or_default();
+            if age < min_age {
+                continue;
+            }
+        }
+        if !policy.dry_run {
+            ensure_run_dir(&root, &run.path)?;
+            fs::remove_dir_all(&run.path)
+                .with_context(|| format!("remove {}", run.path.display()))?;
+        }
+        removed.push(run.path);
+    }
+    Ok(removed)
+}
+
+/// Parse a duration such as `90s`, `30m`, `12h`, or `7d` (bare numbers are seconds).
+pub(crate) fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    let split = value
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (digits, unit) = value.split_at(split);
+    let amount: u64 = digits
+        .parse()
+        .map_err(|_| format!("invalid duration {value:?}"))?;
+    let scale = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(format!("invalid duration unit {unit:?} (use s, m, h, or d)")),
+    };
+    Ok(Duration::from_secs(amount.saturating_mul(scale)))
+}
+
+fn evidence_root(out_dir: &Path) -> Result<PathBuf> {
+    let root = out_dir.join(EVIDENCE_DIR);
+    let metadata = fs::symlink_metadata(&root)
+        .with_context(|| format!("stat evidence root {}", root.display()))?;
+    if !metadata.is_dir() {
+        return Err(anyhow!(
+            "evidence root is not a directory: {}",
+            root.display()
+        ));
+    }
+    let resolved = fs::canonicalize(&root)
+        .with_context(|| format!("resolve evidence root {}", root.display()))?;
+    let expected = fs::canonicalize(out_dir)
+        .with_context(|| format!("resolve out dir {}", out_dir.display()))?
+        .join(EVIDENCE_DIR);
+    if resolved != expected {
+        return Err(anyhow!(
+            "refusing to prune {}: it resolves outside {}",
+            root.display(),
+            expected.display()
+        ));
+    }
+    Ok(resolved)
+}
+
+/// Re-check a run right before removal: it must still be a real directory
+/// (not a symlink swapped in since the scan) directly under `root`.
+fn ensure_run_dir(root: &Path, path: &Path) -> Result<()> {
+    let metadata =
+        fs::symlink_metadata(path).with_context(|| format!("stat {}", path.display()))?;
+    if !metadata.is_dir() {
+        return Err(anyhow!("refusing to prune non-directory {}", path.display()));
+    }
+    let resolved =
+        fs::canonicalize(path).with_context(|| format!("resolve {}", path.display()))?;
+    if resolved.parent() != Some(root) {
+        return Err(anyhow!(
+            "refusing to prune outside evidence root: {}",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+fn scan_runs(root: &Path) -> Result<Vec<RunDir>> {
+    let mut runs = Vec::new();
+    for entry in fs::read_dir(root).with_context(|| format!("read {}", root.display()))? {
+        let entry = entry?;
+        let metadata = fs::symlink_metadata(entry.path())?;
+        // Symlinks and stray files are never followed or removed.
+        if !metadata.is_dir() {
+            continue;
+        }
+        runs.push(RunDir {
+            path: entry.path(),
+            modified: metadata.modified()?,
+        });
+    }
+    Ok(runs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use filetime::FileTime;
+    use std::os::unix::fs::symlink;
+    use tempfile::TempDir;
+
+    /// Create `<out>/evidence/<name>` runs with mtimes `age_secs` in the past.
+    fn make_runs(out: &Path, runs: &[(&str, u64)]) {
+        let root = out.join(EVIDENCE_DIR);
+        fs::create_dir_all(&root).unwrap();
+        let now = SystemTime::now();
+        for (name, age_secs) in runs {
+            let dir = root.join(name);
+            fs::create_dir(&dir).unwrap();
+            fs::write(dir.join("meta.json"), b"{}").unwrap();
+            let mtime = FileTime::from_system_time(now - Duration::from_secs(*age_secs));
+            filetime::set_file_mtime(&dir, mtime).unwrap();
+        }
+    }
+
+    fn names(paths: &[PathBuf]) -> Vec<String> {
+        let mut names: Vec<String> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    fn policy(keep_last: Option<usize>, older_than: Option<u64>, dry_run: bool) -> GcPolicy {
+        GcPolicy {
+            keep_last,
+            older_than: older_than.map(Duration::from_secs),
+            dry_run,
+        }
+    }
+
+    #[test]
+    fn keep_last_never_removes_newest_runs() {
+        let out = TempDir::new().unwrap();
+        make_runs(out.path(), &[("new", 10), ("mid", 100), ("old", 1000)]);
+        let removed = gc_evidence(out.path(), &policy(Some(2), None, false)).unwrap();
+        assert_eq!(names(&removed), ["old"]);
+        let root = out.path().join(EVIDENCE_DIR);
+        assert!(root.join("new").is_dir() && root.join("mid").is_dir());
+        assert!(!root.join("old").exists());
+    }
+
+    #[test]
+    fn older_than_combines_with_keep_last() {
+        let out = TempDir::new().unwrap();
+        make_runs(
+            out.path(),
+            &[("a", 10), ("b", 2 * 3600), ("c", 3 * 3600), ("d", 4 * 3600)],
+        );
+        // Keep the newest two regardless of age; of the rest, only prune > 3.5h.
+        let removed = gc_evidence(out.path(), &policy(Some(2), Some(3 * 3600 + 1800), false))
+            .unwrap();
+        assert_eq!(names(&removed), ["d"]);
+        let removed = gc_evidence(out.path(), &policy(Some(0), Some(3600), false)).unwrap();
+        assert_eq!(names(&removed), ["b", "c"]);
+    }
+
+    #[test]
+    fn dry_run_removes_nothing() {
+        let out = TempDir::new().unwrap();
+        make_runs(out.path(), &[("a", 10), ("b", 20)]);
+        let removed = gc_evidence(out.path(), &policy(Some(0), None, true)).unwrap();
+        assert_eq!(names(&removed), ["a", "b"]);
+        let root = out.path().join(EVIDENCE_DIR);
+        assert!(root.join("a").is_dir() && root.join("b").is_dir());
+    }
+
+    #[test]
+    fn symlinks_and_stray_files_are_skipped() {
+        let out = TempDir::new().unwrap();
+        let outside = TempDir::new().unwrap();
+        make_runs(out.path(), &[("run", 10)]);
+        let root = out.path().join(EVIDENCE_DIR);
+        fs::write(root.join("stray.txt"), b"x").unwrap();
+        symlink(outside.path(), root.join("link")).unwrap();
+        let removed = gc_evidence(out.path(), &policy(Some(0), None, false)).unwrap();
+        assert_eq!(names(&removed), ["run"]);
+        assert!(root.join("stray.txt").is_file());
+        assert!(fs::symlink_metadata(root.join("link")).unwrap().is_symlink());
+        assert!(outside.path().is_dir());
+    }
+
+    #[test]
+    fn refuses_symlinked_evidence_root() {
+        let out = TempDir::new().unwrap();
+        let elsewhere = TempDir::new().unwrap();
+        make_runs(elsewhere.path(), &[("run", 10)]);
+        symlink(elsewhere.path().join(EVIDENCE_DIR), out.path().join(EVIDENCE_DIR)).unwrap();
+        let err = gc_evidence(out.path(), &policy(Some(0), None, false)).unwrap_err();
+        assert!(err.to_string().contains("not a directory"), "{err:#}");
+        assert!(elsewhere.path().join(EVIDENCE_DIR).join("run").is_dir());
+    }
+
+    #[test]
+    fn requires_a_policy() {
+        let out = TempDir::new().unwrap();
+        make_runs(out.path(), &[("run", 10)]);
+        assert!(gc_evidence(out.path(), &policy(None, None, false)).is_err());
+    }
+
+    #[test]
+    fn parse_duration_units_and_errors() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_duration(" 12h "), Ok(Duration::from_secs(12 * 3600)));
+        assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(7 * 86400)));
+        for bad in ["", "d", "7w", "1.5h", "-3s", "7dd"] {
+            assert!(parse_duration(bad).is_err(), "{bad:?}");
+        }
+    }
+}
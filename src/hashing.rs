@@ -12,11 +12,19 @@ pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
     hex::encode(hasher.finalize())
 }
 
-/// Hash a file by reading it fully and returning a lowercase hex string.
+/// Largest read size for streamed file hashing. Hashing is CPU-bound well
+/// below this, so larger reads only cost memory.
+const HASH_CHUNK_BYTES: usize = 1024 * 1024;
+
+/// Hash a file in reads of at most `HASH_CHUNK_BYTES` and return a lowercase
+/// hex string. The buffer is sized to the file, so small files (fixture
+/// entries, artifacts) do not allocate a full chunk.
 pub(crate) fn sha256_file(path: &Path) -> io::Result<String> {
     let mut file = fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    let chunk = usize::try_from(len).map_or(HASH_CHUNK_BYTES, |len| len.min(HASH_CHUNK_BYTES));
     let mut hasher = Sha256::new();
-    let mut buffer = [0u8; 8192];
+    let mut buffer = vec![0u8; chunk.max(1)];
     loop {
         let read = file.read(&mut buffer)?;
         if read == 0 {
@@ -26,3 +34,19 @@ pub(crate) fn sha256_file(path: &Path) -> io::Result<String> {
     }
     Ok(hex::encode(hasher.finalize()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sha256_file_matches_in_memory_hash() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let big: Vec<u8> = (0..HASH_CHUNK_BYTES + 17).map(|i| (i % 251) as u8).collect();
+        for data in [&b""[..], b"x", &big] {
+            let path = dir.path().join("data");
+            fs::write(&path, data).unwrap();
+            assert_eq!(sha256_file(&path).unwrap(), sha256_hex(data), "{} bytes", data.len());
+        }
+    }
+}
@@ -2,13 +2,24 @@
 
 use std::io;
 
-use crate::scenario::ScenarioLimits;
+use crate::scenario::{ScenarioLimits, DEFAULT_STACK_KB};
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type RlimitResource = libc::__rlimit_resource_t;
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+type RlimitResource = libc::c_int;
 
 /// Configure rlimits and session isolation for the child process.
-pub(crate) fn configure_child(limits: ScenarioLimits) -> io::Result<()> {
+///
+/// With `rlimits` false only the new session is created; the wall-time limit
+/// is still enforced by the parent.
+pub(crate) fn configure_child(limits: ScenarioLimits, rlimits: bool) -> io::Result<()> {
     if unsafe { libc::setsid() } == -1 {
         return Err(io::Error::last_os_error());
     }
+    if !rlimits {
+        return Ok(());
+    }
     let cpu_secs = limits.cpu_time_ms.div_ceil(1000);
     set_rlimit(libc::RLIMIT_CPU, cpu_secs, cpu_secs)?;
     set_rlimit(
@@ -21,11 +32,42 @@ pub(crate) fn configure_child(limits: ScenarioLimits) -> io::Result<()> {
         limits.file_size_kb.saturating_mul(1024),
         limits.file_size_kb.saturating_mul(1024),
     )?;
-    set_rlimit(libc::RLIMIT_NOFILE, 128, 128)?;
+    set_rlimit(libc::RLIMIT_NOFILE, limits.open_files, limits.open_files)?;
+    // RLIMIT_NPROC counts every process owned by the user, not just this tree,
+    // so it is only applied when the scenario asks for it.
+    if let Some(max_processes) = limits.max_processes {
+        set_rlimit(libc::RLIMIT_NPROC, max_processes, max_processes)?;
+    }
+    match limits.stack_kb {
+        Some(stack_kb) => {
+            let stack_bytes = stack_kb.saturating_mul(1024);
+            set_rlimit(libc::RLIMIT_STACK, stack_bytes, stack_bytes)?;
+        }
+        None => {
+            // The default only ever lowers the inherited limit, so a parent
+            // that already runs with a smaller stack is not rejected (EPERM).
+            let default_bytes = DEFAULT_STACK_KB.min(limits.memory_kb).saturating_mul(1024);
+            let (cur, max) = get_rlimit(libc::RLIMIT_STACK)?;
+            let max = max.min(default_bytes as libc::rlim_t);
+            set_rlimit(libc::RLIMIT_STACK, cur.min(max), max)?;
+        }
+    }
+    set_rlimit(libc::RLIMIT_CORE, 0, 0)?;
     Ok(())
 }
 
-fn set_rlimit(resource: libc::__rlimit_resource_t, cur: u64, max: u64) -> io::Result<()> {
+fn get_rlimit(resource: RlimitResource) -> io::Result<(libc::rlim_t, libc::rlim_t)> {
+    let mut lim = libc::rlimit {
+        rlim_cur: 0,
+        rlim_max: 0,
+    };
+    if unsafe { libc::getrlimit(resource, &mut lim) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok((lim.rlim_cur, lim.rlim_max))
+}
+
+fn set_rlimit(resource: RlimitResource, cur: u64, max: u64) -> io::Result<()> {
     let lim = libc::rlimit {
         rlim_cur: cur as libc::rlim_t,
         rlim_max: max as libc::rlim_t,
@@ -1,71 +1,347 @@
-//! LM prompt assembly and Claude CLI invocation.
+//! LM prompt assembly and LM backend invocation (CLI command or HTTP).
 
 use anyhow::{anyhow, Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
 use std::env;
 use std::fs;
+use std::hash::{BuildHasher, Hasher};
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
 
-use crate::runner::run_direct;
-use crate::scenario::ScenarioLimits;
+use crate::runner::{run_direct, RunOptions};
+use crate::scenario::{ScenarioLimits, DEFAULT_OPEN_FILES};
 
 const HELP_LIMITS: ScenarioLimits = ScenarioLimits {
     wall_time_ms: 2000,
     cpu_time_ms: 1000,
     memory_kb: 65536,
     file_size_kb: 1024,
+    max_processes: None,
+    open_files: DEFAULT_OPEN_FILES,
+    stack_kb: None,
 };
 
+/// Help text beyond this many bytes is dropped before prompting.
+const HELP_MAX_BYTES: usize = 256 * 1024;
+/// Help listing fewer options than this (see [`count_help_options`]) triggers
+/// [`HELP_ALL_FLAGS`] and then the usage-error probe.
+const HELP_ALL_MIN_OPTIONS: usize = 3;
+/// Flags tried, in order, for tools whose `--help` is only a summary.
+const HELP_ALL_FLAGS: [&str; 2] = ["--help-all", "--help=all"];
+/// Maximum option names listed in the prompt's detected-options section.
+const PROMPT_OPTION_LIMIT: usize = 200;
+/// Prefix of the unrecognized option used to provoke a usage message from
+/// tools without help; each probe appends a random suffix.
+const USAGE_ERROR_ARG: &str = "--bman-usage-probe";
+/// Output markers showing the usage probe was rejected as unknown (matched
+/// case-insensitively); a nonzero exit also counts.
+const USAGE_REJECTION_MARKERS: &[&str] =
+    &["unrecognized", "unknown", "invalid", "illegal", "not recognized"];
+/// System prompt shared by the default CLI command and the HTTP backend.
+const LM_SYSTEM_PROMPT: &str = "Return a single JSON object only. No prose or code fences.";
+/// Overall timeout for a single HTTP completion request.
+const LM_HTTP_TIMEOUT: Duration = Duration::from_secs(300);
+
 #[derive(Deserialize)]
 #[serde(deny_unknown_fields)]
 struct LmCommandConfig {
-    command: Vec<String>,
+    #[serde(default)]
+    command: Option<Vec<String>>,
+    #[serde(default)]
+    http: Option<LmHttpConfig>,
+}
+
+/// OpenAI-compatible chat completions endpoint configuration.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LmHttpConfig {
+    /// Base URL (e.g. `https://api.openai.com/v1`) or a full `/chat/completions` URL.
+    pub(crate) url: String,
+    pub(crate) model: String,
+    /// Name of the environment variable holding the bearer token, if any.
+    #[serde(default)]
+    pub(crate) api_key_env: Option<String>,
+}
+
+/// Retry policy for LM invocations that fail or return non-JSON output.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct LmRetry {
+    pub(crate) attempts: u32,
+    pub(crate) backoff_ms: u64,
+}
+
+/// LM output as received (`raw`) and as accepted for parsing (`json`).
+pub(crate) struct LmResponse {
+    pub(crate) raw: Vec<u8>,
+    pub(crate) json: Vec<u8>,
+    pub(crate) attempts: u32,
+}
+
+/// Configured LM backend.
+pub(crate) enum LmCommand {
+    /// Local command; `{prompt}`/`{schema}` placeholders are substituted.
+    Argv(Vec<String>),
+    /// OpenAI-compatible HTTP endpoint.
+    Http(LmHttpConfig),
+}
+
+impl LmCommand {
+    /// Short description for transcripts (program name or endpoint URL).
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            LmCommand::Argv(argv) => format!(
+                "program={} args={}",
+                argv.first().map(|value| value.as_str()).unwrap_or("<unknown>"),
+                argv.len().saturating_sub(1)
+            ),
+            LmCommand::Http(config) => {
+                format!("http url={} model={}", chat_completions_url(&config.url), config.model)
+            }
+        }
+    }
+}
+
+/// Which output stream of the help command to treat as help text.
+#[derive(clap::ValueEnum, Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub(crate) enum HelpStream {
+    Stdout,
+    Stderr,
+    /// Prefer the stream with more option-like lines (ties go to stdout).
+    #[default]
+    Auto,
 }
 
-pub(crate) struct LmCommand {
-    pub(crate) argv: Vec<String>,
+/// How the help command is invoked.
+pub(crate) struct HelpOptions<'a> {
+    pub(crate) stream: HelpStream,
+    /// Args passed before the help flag.
+    pub(crate) preamble: &'a [String],
+    /// Exact help invocation from `--help-args`; disables all fallbacks.
+    pub(crate) help_args: &'a [String],
+    /// Working directory; `None` runs in a fresh empty temp dir.
+    pub(crate) cwd: Option<&'a Path>,
+    /// First usage-probe token from `--usage-probe-arg`; `None` generates one.
+    pub(crate) usage_probe_arg: Option<&'a str>,
 }
 
 pub(crate) struct HelpCapture {
     pub(crate) bytes: Vec<u8>,
     pub(crate) source: &'static str,
-    pub(crate) flag: &'static str,
+    /// Help invocation that produced `bytes`, space-joined (e.g. `--help`).
+    pub(crate) flag: String,
+    /// Output exceeded [`HELP_MAX_BYTES`] and was cut off.
+    pub(crate) truncated: bool,
+    /// The help command hit its wall-time limit; `bytes` is partial output.
+    pub(crate) timed_out: bool,
+    /// Usage-probe token last tried, if the usage fallback ran.
+    pub(crate) usage_probe: Option<String>,
+    exit_code: Option<i32>,
+}
+
+/// How captured help bytes were decoded into prompt text.
+#[derive(Serialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum HelpEncoding {
+    /// Valid UTF-8.
+    Utf8,
+    /// Invalid UTF-8 replaced with U+FFFD; parsing may be degraded.
+    Lossy,
+    /// Invalid byte runs decoded as Windows-1252 because they sat inside
+    /// option-like tokens (e.g. an en dash written as byte 0x96); valid UTF-8
+    /// elsewhere in the text is kept as is.
+    Windows1252,
+}
+
+/// Decode help bytes, preferring UTF-8 and falling back to Windows-1252 only
+/// when lossy decoding would corrupt an option-like token.
+pub(crate) fn decode_help_text(bytes: &[u8]) -> (String, HelpEncoding) {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return (text.to_string(), HelpEncoding::Utf8);
+    }
+    let lossy = String::from_utf8_lossy(bytes).into_owned();
+    let corrupts_option = lossy.split_whitespace().any(is_corrupted_option_token);
+    if corrupts_option {
+        let mut decoded = String::with_capacity(bytes.len());
+        for chunk in bytes.utf8_chunks() {
+            decoded.push_str(chunk.valid());
+            decoded.extend(chunk.invalid().iter().map(|&byte| windows1252_char(byte)));
+        }
+        return (decoded, HelpEncoding::Windows1252);
+    }
+    (lossy, HelpEncoding::Lossy)
+}
+
+/// Windows-1252 characters for bytes 0x80..=0x9F; the five unassigned bytes
+/// map to U+FFFD.
+const WINDOWS1252_C1: [char; 32] = [
+    '\u{20AC}', '\u{FFFD}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{FFFD}', '\u{017D}', '\u{FFFD}',
+    '\u{FFFD}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{FFFD}', '\u{017E}', '\u{0178}',
+];
+
+/// Map a byte to its Windows-1252 character (Latin-1 outside 0x80..=0x9F).
+fn windows1252_char(byte: u8) -> char {
+    match byte {
+        0x80..=0x9f => WINDOWS1252_C1[usize::from(byte - 0x80)],
+        _ => byte as char,
+    }
+}
+
+/// True when a replacement character sits in a token that looks like an
+/// option, e.g. `-\u{FFFD}x` or a Latin-1 dash prefix such as `\u{FFFD}verbose`.
+fn is_corrupted_option_token(token: &str) -> bool {
+    if !token.contains(char::REPLACEMENT_CHARACTER) {
+        return false;
+    }
+    if token.starts_with('-') {
+        return true;
+    }
+    let rest = token.trim_start_matches(char::REPLACEMENT_CHARACTER);
+    rest.len() != token.len()
+        && rest.len() > 1
+        && rest.starts_with(|ch: char| ch.is_ascii_alphabetic())
+        && rest
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '=' | ','))
 }
 
 /// Capture help text for a binary using `--help`, falling back to `-h`.
-pub(crate) fn capture_help(binary: &Path) -> Result<HelpCapture> {
-    let cwd = std::env::current_dir().context("resolve cwd for help")?;
-    let output = capture_help_with_arg(binary, "--help", &cwd)?;
-    if !output.bytes.is_empty() {
+///
+/// When that lists fewer than [`HELP_ALL_MIN_OPTIONS`] options,
+/// [`HELP_ALL_FLAGS`] are tried for a fuller listing; if the result still lists
+/// fewer, the usage error printed for a usage-probe token is used instead when
+/// it lists more options (see [`capture_usage_error`]). `--help-args` replaces
+/// all of this with one exact invocation.
+///
+/// Only [`HELP_MAX_BYTES`] of each stream are kept (the rest is drained and
+/// discarded), and a timed-out command still yields its partial output; only a
+/// timeout with no output at all is an error.
+pub(crate) fn capture_help(binary: &Path, options: &HelpOptions) -> Result<HelpCapture> {
+    let temp_dir;
+    let cwd = match options.cwd {
+        Some(cwd) => cwd,
+        None => {
+            temp_dir = TempDir::new().context("create temp dir for help")?;
+            temp_dir.path()
+        }
+    };
+    let capture = |help_args: &[&str]| capture_help_with_args(binary, options, help_args, cwd);
+    if !options.help_args.is_empty() {
+        let help_args: Vec<&str> = options.help_args.iter().map(String::as_str).collect();
+        let output = capture(&help_args)?;
+        if output.bytes.is_empty() && output.timed_out {
+            return Err(anyhow!("help command timed out"));
+        }
         return Ok(output);
     }
-    capture_help_with_arg(binary, "-h", &cwd)
+    let mut output = capture(&["--help"])?;
+    if output.bytes.is_empty() {
+        output = capture(&["-h"])?;
+    }
+    if output.bytes.is_empty() && output.timed_out {
+        return Err(anyhow!("help command timed out"));
+    }
+    let option_count = count_help_options(&output.bytes);
+    if option_count < HELP_ALL_MIN_OPTIONS {
+        for flag in HELP_ALL_FLAGS {
+            match capture(&[flag]) {
+                Ok(all) if count_help_options(&all.bytes) > option_count => {
+                    output = all;
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+    let option_count = count_help_options(&output.bytes);
+    if option_count < HELP_ALL_MIN_OPTIONS {
+        let (usage, token) = capture_usage_error(options.usage_probe_arg, capture);
+        if let Some(usage) = usage.filter(|usage| count_help_options(&usage.bytes) > option_count)
+        {
+            return Ok(usage);
+        }
+        output.usage_probe = Some(token);
+    }
+    Ok(output)
+}
+
+/// Provoke a usage error with an unknown option, returning the capture (if the
+/// tool rejected the token) and the last token tried.
+///
+/// The token is `usage_probe_arg` or [`USAGE_ERROR_ARG`] plus a random suffix.
+/// A tool that exits zero without a rejection marker may actually define it,
+/// so one freshly generated token is tried before giving up.
+fn capture_usage_error(
+    usage_probe_arg: Option<&str>,
+    capture: impl Fn(&[&str]) -> Result<HelpCapture>,
+) -> (Option<HelpCapture>, String) {
+    let mut token = usage_probe_arg.map_or_else(usage_probe_token, str::to_string);
+    for attempt in 0..2 {
+        if attempt > 0 {
+            token = usage_probe_token();
+        }
+        let Ok(mut usage) = capture(&[&token]) else {
+            continue;
+        };
+        let text = String::from_utf8_lossy(&usage.bytes).to_lowercase();
+        let rejected = usage.exit_code.is_some_and(|code| code != 0)
+            || USAGE_REJECTION_MARKERS.iter().any(|marker| text.contains(marker));
+        if rejected {
+            usage.usage_probe = Some(token.clone());
+            return (Some(usage), token);
+        }
+    }
+    (None, token)
+}
+
+/// [`USAGE_ERROR_ARG`] with a per-call random hex suffix.
+fn usage_probe_token() -> String {
+    // RandomState is seeded randomly per instance, so this needs no RNG crate.
+    let suffix = RandomState::new().build_hasher().finish();
+    format!("{USAGE_ERROR_ARG}-{suffix:016x}")
 }
 
 /// Load the LM command configuration, falling back to Claude defaults.
 pub(crate) fn load_lm_command() -> Result<LmCommand> {
     if let Ok(raw) = env::var("BMAN_LM_COMMAND") {
-        let argv = parse_command_config(&raw)
-            .context("parse BMAN_LM_COMMAND")?;
-        return Ok(LmCommand { argv });
+        return parse_command_config(&raw).context("parse BMAN_LM_COMMAND");
     }
     Ok(default_lm_command())
 }
 
-fn parse_command_config(raw: &str) -> Result<Vec<String>> {
+fn parse_command_config(raw: &str) -> Result<LmCommand> {
     let config: LmCommandConfig =
         serde_json::from_str(raw).context("parse LM command JSON")?;
-    if config.command.is_empty() {
-        return Err(anyhow!("LM command is empty"));
+    match (config.command, config.http) {
+        (Some(_), Some(_)) => Err(anyhow!("LM config must set only one of command or http")),
+        (Some(command), None) => {
+            if command.is_empty() {
+                return Err(anyhow!("LM command is empty"));
+            }
+            Ok(LmCommand::Argv(command))
+        }
+        (None, Some(http)) => {
+            if http.url.trim().is_empty() {
+                return Err(anyhow!("LM http.url is empty"));
+            }
+            if http.model.trim().is_empty() {
+                return Err(anyhow!("LM http.model is empty"));
+            }
+            Ok(LmCommand::Http(http))
+        }
+        (None, None) => Err(anyhow!("LM config must set command or http")),
     }
-    Ok(config.command)
 }
 
 fn default_lm_command() -> LmCommand {
-    LmCommand {
-        argv: vec![
+    LmCommand::Argv(vec![
             "claude".to_string(),
             "--print".to_string(),
             "--output-format".to_string(),
@@ -74,33 +350,186 @@ fn default_lm_command() -> LmCommand {
             "{schema}".to_string(),
             "--no-session-persistence".to_string(),
             "--system-prompt".to_string(),
-            "Return a single JSON object only. No prose or code fences.".to_string(),
+            LM_SYSTEM_PROMPT.to_string(),
             "--tools".to_string(),
             "".to_string(),
-        ],
-    }
+        ])
 }
 
-fn capture_help_with_arg(binary: &Path, flag: &'static str, cwd: &Path) -> Result<HelpCapture> {
-    let args = vec![flag.to_string()];
-    let result = run_direct(binary, &args, cwd, HELP_LIMITS).context("run help command")?;
-    if result.timed_out {
-        return Err(anyhow!("help command timed out"));
-    }
-    if !result.stdout.is_empty() {
-        return Ok(HelpCapture {
-            bytes: result.stdout,
-            source: "stdout",
-            flag,
-        });
+fn capture_help_with_args(
+    binary: &Path,
+    options: &HelpOptions,
+    help_args: &[&str],
+    cwd: &Path,
+) -> Result<HelpCapture> {
+    let mut args = options.preamble.to_vec();
+    args.extend(help_args.iter().map(|arg| arg.to_string()));
+    let run_options = RunOptions {
+        max_output_bytes: Some(HELP_MAX_BYTES),
+        ..RunOptions::default()
+    };
+    let result = run_direct(binary, &args, cwd, HELP_LIMITS, run_options)
+        .context("run help command")?;
+    let (mut bytes, source) = select_help_text(options.stream, result.stdout, result.stderr);
+    let truncated = bytes.len() > HELP_MAX_BYTES;
+    if truncated {
+        bytes.truncate(HELP_MAX_BYTES);
+        // Do not leave a split UTF-8 sequence that would force lossy decoding.
+        if let Err(err) = std::str::from_utf8(&bytes) {
+            if err.error_len().is_none() {
+                bytes.truncate(err.valid_up_to());
+            }
+        }
     }
     Ok(HelpCapture {
-        bytes: result.stderr,
-        source: "stderr",
-        flag,
+        bytes,
+        source,
+        flag: help_args.join(" "),
+        truncated,
+        timed_out: result.timed_out,
+        usage_probe: None,
+        exit_code: result.exit_code,
     })
 }
 
+/// Pick the help stream: forced by `stream`, or under `Auto` the one listing
+/// more options (stdout on a tie unless it is empty).
+fn select_help_text(
+    stream: HelpStream,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+) -> (Vec<u8>, &'static str) {
+    let use_stdout = match stream {
+        HelpStream::Stdout => true,
+        HelpStream::Stderr => false,
+        HelpStream::Auto => {
+            let stdout_options = count_help_options(&stdout);
+            let stderr_options = count_help_options(&stderr);
+            if stdout_options != stderr_options {
+                stdout_options > stderr_options
+            } else {
+                !stdout.is_empty()
+            }
+        }
+    };
+    if use_stdout {
+        (stdout, "stdout")
+    } else {
+        (stderr, "stderr")
+    }
+}
+
+/// Markers of output that is an error report rather than help (matched
+/// case-insensitively).
+const HELP_ERROR_MARKERS: &[&str] = &[
+    "command not found",
+    "traceback (most recent call last)",
+    "panicked at",
+    "segmentation fault",
+    "core dumped",
+    "error while loading shared libraries",
+    "exec format error",
+];
+
+/// Return the error marker found in help output that lists no options.
+///
+/// Output that lists options (option lines or a usage synopsis) is never
+/// classified, so help text that merely mentions e.g. "core dumped" is still
+/// accepted.
+pub(crate) fn help_error_marker(bytes: &[u8]) -> Option<&'static str> {
+    if count_help_options(bytes) > 0 {
+        return None;
+    }
+    let text = String::from_utf8_lossy(bytes).to_lowercase();
+    HELP_ERROR_MARKERS
+        .iter()
+        .copied()
+        .find(|marker| text.contains(marker))
+}
+
+/// Count documented options: the larger of the number of lines that start
+/// (after indentation) with an option such as `-x` or `--long`, and the number
+/// of bracketed options on `usage:` synopsis lines. Taking the larger keeps a
+/// synopsis that restates the option list from being counted twice.
+fn count_help_options(bytes: &[u8]) -> usize {
+    let text = String::from_utf8_lossy(bytes);
+    let option_lines = help_lines(&text)
+        .filter(|line| is_option_token(line.trim_start()))
+        .count();
+    option_lines.max(count_synopsis_options(&text))
+}
+
+/// Count options in `[...]` groups on `usage:` lines and their indented
+/// continuation lines, e.g. 4 for `usage: tool [-abc] [--long=ARG] file...`.
+/// A clustered short group such as `-abc` counts once per letter.
+fn count_synopsis_options(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_synopsis = false;
+    for line in help_lines(text) {
+        let trimmed = line.trim_start();
+        let synopsis = match trimmed.get(..6) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("usage:") => &trimmed[6..],
+            _ if in_synopsis && line.len() > trimmed.len() && trimmed.starts_with('[') => {
+                trimmed
+            }
+            _ => {
+                in_synopsis = false;
+                continue;
+            }
+        };
+        in_synopsis = true;
+        for group in synopsis.split('[').skip(1) {
+            let group = group.split(']').next().unwrap_or(group);
+            for word in group.split(|ch: char| ch.is_whitespace() || ch == '|') {
+                if !is_option_token(word) {
+                    continue;
+                }
+                count += match word.strip_prefix('-') {
+                    Some(short) if !short.starts_with('-') => short
+                        .chars()
+                        .take_while(|ch| ch.is_ascii_alphanumeric())
+                        .count(),
+                    _ => 1,
+                };
+            }
+        }
+    }
+    count
+}
+
+/// Split help text on `\n`, `\r\n`, or a lone `\r` (progress redraws), so no
+/// carriage return is left inside a line. Empty pieces (including the one
+/// between `\r` and `\n`) are skipped; callers only look at option lines.
+fn help_lines(text: &str) -> impl Iterator<Item = &str> {
+    text.split(['\n', '\r']).filter(|line| !line.is_empty())
+}
+
+/// True for tokens such as `-x`, `--long`, or `--long=VALUE`.
+fn is_option_token(token: &str) -> bool {
+    let name = token.strip_prefix("--").or_else(|| token.strip_prefix('-'));
+    name.is_some_and(|name| name.starts_with(|ch: char| ch.is_ascii_alphanumeric()))
+}
+
+/// Option names from the leading tokens of option lines, deduplicated in
+/// order of appearance (e.g. `-a, --all` yields `-a` and `--all`).
+fn extract_option_tokens(help_text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut tokens = Vec::new();
+    for line in help_lines(help_text) {
+        for word in line.split_whitespace() {
+            let word = word.trim_end_matches(',');
+            if !is_option_token(word) {
+                break;
+            }
+            let name = word.split(['=', '[']).next().unwrap_or(word);
+            if seen.insert(name.to_string()) {
+                tokens.push(name.to_string());
+            }
+        }
+    }
+    tokens
+}
+
 /// Load a UTF-8 file into a string for prompt assembly.
 pub(crate) fn load_text(path: &Path) -> Result<String> {
     let bytes = fs::read(path).with_context(|| format!("read {}", path.display()))?;
@@ -114,6 +543,8 @@ pub(crate) fn build_prompt(
     schema_text: &str,
     catalog_text: &str,
     example_text: Option<&str>,
+    preamble: &[String],
+    include_options: bool,
 ) -> String {
     let mut prompt = String::new();
     prompt.push_str("Return a single JSON object that conforms to the schema below.\n");
@@ -123,6 +554,11 @@ pub(crate) fn build_prompt(
     prompt.push_str("Begin with '{' and end with '}'.\n\n");
     prompt.push_str("Target binary path (must match exactly):\n");
     prompt.push_str(&format!("{}\n\n", binary_path.display()));
+    if !preamble.is_empty() {
+        prompt.push_str("These args are always passed first; do not repeat them in args:\n");
+        prompt.push_str(&serde_json::to_string(preamble).unwrap_or_default());
+        prompt.push_str("\n\n");
+    }
     prompt.push_str("Fixture catalog (allowed fixture.id values):\n");
     prompt.push_str(catalog_text);
     prompt.push_str("\n\nSchema:\n");
@@ -131,6 +567,9 @@ pub(crate) fn build_prompt(
         prompt.push_str("\n\nExample (format only; replace values as needed):\n");
         prompt.push_str(example);
     }
+    if include_options {
+        push_option_tokens(&mut prompt, help_text);
+    }
     prompt.push_str("\n\nRaw help text:\n");
     prompt.push_str(help_text);
     prompt.push_str(
@@ -145,12 +584,95 @@ pub(crate) fn build_prompt(
     prompt
 }
 
-/// Invoke Claude CLI to obtain a scenario JSON response.
-pub(crate) fn run_lm(prompt: &str, schema: &str, command: &LmCommand) -> Result<Vec<u8>> {
-    if command.argv.is_empty() {
+/// Append the detected option names, capped at [`PROMPT_OPTION_LIMIT`].
+fn push_option_tokens(prompt: &mut String, help_text: &str) {
+    let tokens = extract_option_tokens(help_text);
+    if tokens.is_empty() {
+        return;
+    }
+    prompt.push_str(
+        "\n\nOptions detected in the help text (guidance; help text is authoritative):\n",
+    );
+    let shown = tokens.len().min(PROMPT_OPTION_LIMIT);
+    prompt.push_str(&tokens[..shown].join(" "));
+    if tokens.len() > shown {
+        prompt.push_str(&format!("\n({} more omitted)", tokens.len() - shown));
+    }
+}
+
+/// Invoke the configured LM backend until it returns a single JSON object.
+///
+/// Failed invocations and non-JSON output are retried with exponential backoff
+/// up to `retry.attempts` times; persistent failure reports every attempt.
+pub(crate) fn run_lm(
+    prompt: &str,
+    schema: &str,
+    command: &LmCommand,
+    retry: LmRetry,
+) -> Result<LmResponse> {
+    let attempts = retry.attempts.max(1);
+    let mut failures = Vec::new();
+    for attempt in 1..=attempts {
+        if attempt > 1 {
+            let backoff = retry.backoff_ms.saturating_mul(1 << (attempt - 2).min(16));
+            thread::sleep(Duration::from_millis(backoff));
+        }
+        let raw = match invoke_lm(prompt, schema, command) {
+            Ok(raw) => raw,
+            Err(err) => {
+                failures.push(format!("attempt {attempt}: {err:#}"));
+                continue;
+            }
+        };
+        match extract_json_object(&raw) {
+            Ok(json) => {
+                return Ok(LmResponse {
+                    raw,
+                    json,
+                    attempts: attempt,
+                })
+            }
+            Err(err) => failures.push(format!("attempt {attempt}: {err}")),
+        }
+    }
+    Err(anyhow!(
+        "LM failed after {attempts} attempt(s): {}",
+        failures.join("; ")
+    ))
+}
+
+/// Accept output only if it is a single JSON object, tolerating surrounding
+/// whitespace and an accidental Markdown code fence.
+fn extract_json_object(raw: &[u8]) -> Result<Vec<u8>> {
+    let text = std::str::from_utf8(raw).context("LM output is not UTF-8")?;
+    let mut body = text.trim();
+    if let Some(rest) = body.strip_prefix("```") {
+        let rest = rest.strip_prefix("json").unwrap_or(rest);
+        body = rest.strip_suffix("```").unwrap_or(rest).trim();
+    }
+    if !body.starts_with('{') {
+        return Err(anyhow!("LM output does not start with '{{'"));
+    }
+    let value: serde_json::Value =
+        serde_json::from_str(body).context("LM output is not valid JSON")?;
+    if !value.is_object() {
+        return Err(anyhow!("LM output is not a JSON object"));
+    }
+    Ok(body.as_bytes().to_vec())
+}
+
+fn invoke_lm(prompt: &str, schema: &str, command: &LmCommand) -> Result<Vec<u8>> {
+    match command {
+        LmCommand::Argv(argv) => run_lm_command(prompt, schema, argv),
+        LmCommand::Http(config) => run_lm_http(prompt, schema, config),
+    }
+}
+
+fn run_lm_command(prompt: &str, schema: &str, argv: &[String]) -> Result<Vec<u8>> {
+    if argv.is_empty() {
         return Err(anyhow!("LM command is empty"));
     }
-    let mut argv = command.argv.clone();
+    let mut argv = argv.to_vec();
     let mut has_placeholder = false;
     for arg in &mut argv {
         if arg == "{prompt}" {
@@ -190,6 +712,70 @@ pub(crate) fn run_lm(prompt: &str, schema: &str, command: &LmCommand) -> Result<
     Ok(output.stdout)
 }
 
+/// POST the prompt to an OpenAI-compatible `/chat/completions` endpoint and
+/// return the assistant message content.
+fn run_lm_http(prompt: &str, schema: &str, config: &LmHttpConfig) -> Result<Vec<u8>> {
+    let schema_value: serde_json::Value =
+        serde_json::from_str(schema).context("parse LM schema for HTTP request")?;
+    let body = serde_json::json!({
+        "model": config.model,
+        "messages": [
+            { "role": "system", "content": LM_SYSTEM_PROMPT },
+            { "role": "user", "content": prompt },
+        ],
+        "response_format": {
+            "type": "json_schema",
+            "json_schema": { "name": "scenario", "schema": schema_value },
+        },
+    });
+    let body = serde_json::to_string(&body).context("serialize LM HTTP request")?;
+
+    let url = chat_completions_url(&config.url);
+    let agent = ureq::AgentBuilder::new().timeout(LM_HTTP_TIMEOUT).build();
+    let mut request = agent.post(&url).set("Content-Type", "application/json");
+    if let Some(key_env) = &config.api_key_env {
+        let key = env::var(key_env)
+            .with_context(|| format!("read LM API key from ${key_env}"))?;
+        request = request.set("Authorization", &format!("Bearer {key}"));
+    }
+    let response = match request.send_string(&body) {
+        Ok(response) => response,
+        Err(ureq::Error::Status(code, response)) => {
+            let text = response.into_string().unwrap_or_default();
+            return Err(anyhow!("LM HTTP request failed with status {code}: {}", text.trim()));
+        }
+        Err(err) => return Err(anyhow!("LM HTTP request failed: {err}")),
+    };
+    let text = response.into_string().context("read LM HTTP response")?;
+    completion_content(&text)
+}
+
+/// Extract `choices[0].message.content` from a chat completions response body.
+fn completion_content(text: &str) -> Result<Vec<u8>> {
+    let value: serde_json::Value =
+        serde_json::from_str(text).context("parse LM HTTP response JSON")?;
+    let content = value
+        .get("choices")
+        .and_then(|choices| choices.get(0))
+        .and_then(|choice| choice.get("message"))
+        .and_then(|message| message.get("content"))
+        .and_then(|content| content.as_str())
+        .ok_or_else(|| anyhow!("LM HTTP response has no choices[0].message.content"))?;
+    if content.trim().is_empty() {
+        return Err(anyhow!("LM HTTP response content is empty"));
+    }
+    Ok(content.as_bytes().to_vec())
+}
+
+fn chat_completions_url(base: &str) -> String {
+    let base = base.trim_end_matches('/');
+    if base.ends_with("/chat/completions") {
+        base.to_string()
+    } else {
+        format!("{base}/chat/completions")
+    }
+}
+
 /// Resolve paths for prompt assets.
 pub(crate) fn scenario_schema_path(root: &Path) -> PathBuf {
     root.join("schema").join("scenario.v0.json")
@@ -206,3 +792,414 @@ pub(crate) fn fixture_catalog_path(root: &Path) -> PathBuf {
 pub(crate) fn example_scenario_path(root: &Path) -> PathBuf {
     root.join("scenarios").join("examples").join("ls_help.json")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_error(raw: &str) -> String {
+        match parse_command_config(raw) {
+            Ok(_) => panic!("{raw} should be rejected"),
+            Err(err) => format!("{err:#}"),
+        }
+    }
+
+    #[test]
+    fn parse_command_config_requires_exactly_one_backend() {
+        let both = r#"{"command": ["lm"], "http": {"url": "http://x", "model": "m"}}"#;
+        assert!(config_error(both).contains("only one of command or http"));
+        assert!(config_error("{}").contains("must set command or http"));
+        assert!(config_error(r#"{"command": []}"#).contains("LM command is empty"));
+    }
+
+    #[test]
+    fn parse_command_config_rejects_empty_http_fields() {
+        let no_url = r#"{"http": {"url": " ", "model": "m"}}"#;
+        assert!(config_error(no_url).contains("http.url is empty"));
+        let no_model = r#"{"http": {"url": "http://x", "model": ""}}"#;
+        assert!(config_error(no_model).contains("http.model is empty"));
+    }
+
+    #[test]
+    fn parse_command_config_accepts_each_backend() {
+        let http = r#"{"http": {"url": "http://x/v1", "model": "m", "api_key_env": "K"}}"#;
+        match parse_command_config(http).unwrap() {
+            LmCommand::Http(config) => {
+                assert_eq!(config.model, "m");
+                assert_eq!(config.api_key_env.as_deref(), Some("K"));
+            }
+            LmCommand::Argv(_) => panic!("expected http"),
+        }
+        match parse_command_config(r#"{"command": ["lm", "{prompt}"]}"#).unwrap() {
+            LmCommand::Argv(argv) => assert_eq!(argv, ["lm", "{prompt}"]),
+            LmCommand::Http(_) => panic!("expected command"),
+        }
+    }
+
+    #[test]
+    fn chat_completions_url_appends_suffix_once() {
+        for base in [
+            "http://host/v1",
+            "http://host/v1/",
+            "http://host/v1/chat/completions",
+            "http://host/v1/chat/completions/",
+        ] {
+            assert_eq!(chat_completions_url(base), "http://host/v1/chat/completions", "{base}");
+        }
+    }
+
+    #[test]
+    fn completion_content_extracts_first_choice() {
+        let body = r#"{"choices": [{"message": {"content": "{\"a\": 1}"}}]}"#;
+        assert_eq!(completion_content(body).unwrap(), br#"{"a": 1}"#);
+        for bad in [
+            r#"{"choices": []}"#,
+            r#"{"error": "x"}"#,
+            r#"{"choices": [{"message": {"content": "  "}}]}"#,
+            "not json",
+        ] {
+            assert!(completion_content(bad).is_err(), "{bad}");
+        }
+    }
+
+    /// Serve one HTTP request on a local port with `status` and `body`.
+    fn serve_once(status: &str, body: &'static str) -> String {
+        use std::io::{BufRead, BufReader, Read as _};
+        use std::net::TcpListener;
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let status = status.to_string();
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+                let lower = line.to_ascii_lowercase();
+                if let Some(value) = lower.strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut request_body = vec![0u8; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+            let response = format!(
+                "HTTP/1.1 {status}\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            reader.into_inner().write_all(response.as_bytes()).unwrap();
+        });
+        format!("http://{addr}/v1")
+    }
+
+    fn http_config(url: String) -> LmHttpConfig {
+        LmHttpConfig {
+            url,
+            model: "test".to_string(),
+            api_key_env: None,
+        }
+    }
+
+    #[test]
+    fn run_lm_http_returns_message_content() {
+        let url = serve_once("200 OK", r#"{"choices":[{"message":{"content":"{}"}}]}"#);
+        assert_eq!(run_lm_http("p", "{}", &http_config(url)).unwrap(), b"{}");
+    }
+
+    #[test]
+    fn run_lm_http_reports_non_2xx_status() {
+        let url = serve_once("503 Service Unavailable", r#"{"error":"busy"}"#);
+        let err = run_lm_http("p", "{}", &http_config(url)).unwrap_err();
+        assert!(err.to_string().contains("status 503"), "{err:#}");
+    }
+
+    #[test]
+    fn run_lm_http_rejects_missing_choices() {
+        let url = serve_once("200 OK", r#"{"id":"x"}"#);
+        let err = run_lm_http("p", "{}", &http_config(url)).unwrap_err();
+        assert!(err.to_string().contains("no choices[0].message.content"), "{err:#}");
+    }
+
+    #[test]
+    fn extract_json_object_strips_code_fences() {
+        for raw in ["```json\n{\"a\": 1}\n```", "```\n{\"a\": 1}\n```", "  {\"a\": 1}\n"] {
+            assert_eq!(extract_json_object(raw.as_bytes()).unwrap(), br#"{"a": 1}"#, "{raw}");
+        }
+    }
+
+    #[test]
+    fn extract_json_object_rejects_non_objects() {
+        for (raw, message) in [
+            ("Here is the scenario: {\"a\": 1}", "does not start with '{'"),
+            ("[{\"a\": 1}]", "does not start with '{'"),
+            ("{\"a\": 1", "not valid JSON"),
+            ("```json\n{\"a\": }\n```", "not valid JSON"),
+        ] {
+            let err = extract_json_object(raw.as_bytes()).unwrap_err();
+            assert!(err.to_string().contains(message), "{raw}: {err:#}");
+        }
+    }
+
+    #[test]
+    fn run_lm_retries_after_a_failed_attempt() {
+        let dir = TempDir::new().unwrap();
+        let marker = dir.path().join("failed-once");
+        let tool = write_script(
+            dir.path(),
+            &format!(
+                "if [ -e '{0}' ]; then echo '{{\"ok\": true}}'; \
+                 else touch '{0}'; echo 'not json'; fi\n",
+                marker.display()
+            ),
+        );
+        let command = LmCommand::Argv(vec![tool.display().to_string(), "{prompt}".to_string()]);
+        let retry = LmRetry {
+            attempts: 2,
+            backoff_ms: 0,
+        };
+        let response = run_lm("p", "{}", &command, retry).unwrap();
+        assert_eq!(response.attempts, 2);
+        assert_eq!(response.json, br#"{"ok": true}"#);
+
+        fs::remove_file(&marker).unwrap();
+        let retry = LmRetry {
+            attempts: 1,
+            backoff_ms: 0,
+        };
+        assert!(run_lm("p", "{}", &command, retry).is_err());
+    }
+
+    #[test]
+    fn help_lines_split_every_line_ending() {
+        for text in ["a\rb", "a\r\nb", "a\n\rb", "a\nb\n"] {
+            assert_eq!(help_lines(text).collect::<Vec<_>>(), ["a", "b"], "{text:?}");
+        }
+    }
+
+    #[test]
+    fn count_help_options_ignores_line_ending_style() {
+        for text in [
+            "  -a  all\r  -b  bee",
+            "  -a  all\r\n  -b  bee\r\n",
+            "  -a  all\n\r  -b  bee",
+            "loading...\r  -a  all\r\n\r\n  -b  bee\n",
+        ] {
+            assert_eq!(count_help_options(text.as_bytes()), 2, "{text:?}");
+            assert_eq!(extract_option_tokens(text), ["-a", "-b"], "{text:?}");
+        }
+    }
+
+    #[test]
+    fn count_help_options_reads_usage_synopsis() {
+        let synopsis = "usage: tool [-abc] [--long=ARG] file...\n";
+        assert_eq!(count_help_options(synopsis.as_bytes()), 4);
+        let wrapped =
+            "Usage: tool [-v | --verbose] [-o FILE]\n           [--color[=WHEN]] [file ...]\n";
+        assert_eq!(count_help_options(wrapped.as_bytes()), 4);
+        let restated = "usage: tool [-a] [-b]\n  -a  all\n  -b  bee\n";
+        assert_eq!(count_help_options(restated.as_bytes()), 2);
+        assert_eq!(count_help_options(b"usage: tool file...\n[-x] not a synopsis\n"), 0);
+    }
+
+    #[test]
+    fn help_error_marker_classifies_failures_without_options() {
+        let traceback = "Traceback (most recent call last):\n  File \"tool.py\", line 3\n\
+                         ImportError: No module named foo\n";
+        assert_eq!(
+            help_error_marker(traceback.as_bytes()),
+            Some("traceback (most recent call last)")
+        );
+        assert_eq!(
+            help_error_marker(b"sh: 1: tool: Command Not Found\n"),
+            Some("command not found")
+        );
+        assert_eq!(help_error_marker(b"tool 1.0: prints a greeting\n"), None);
+    }
+
+    #[test]
+    fn help_error_marker_ignores_output_that_lists_options() {
+        let help = "Usage: tool [OPTIONS]\n  -c, --core  keep cores (core dumped on crash)\n";
+        assert_eq!(help_error_marker(help.as_bytes()), None);
+        let synopsis = "usage: tool [-v] file\nsegmentation fault is reported as exit 139\n";
+        assert_eq!(help_error_marker(synopsis.as_bytes()), None);
+    }
+
+    #[test]
+    fn decode_help_text_maps_invalid_option_bytes_as_windows1252() {
+        let (text, encoding) = decode_help_text(b"  \x96\x96verbose  be chatty\n");
+        assert_eq!(encoding, HelpEncoding::Windows1252);
+        assert_eq!(text, "  \u{2013}\u{2013}verbose  be chatty\n");
+    }
+
+    #[test]
+    fn decode_help_text_uses_full_c1_table() {
+        let (text, encoding) = decode_help_text(b"-\x80x \x85 \x99 \x8a \x9f \x81");
+        assert_eq!(encoding, HelpEncoding::Windows1252);
+        assert_eq!(text, "-\u{20AC}x \u{2026} \u{2122} \u{0160} \u{0178} \u{FFFD}");
+    }
+
+    #[test]
+    fn decode_help_text_keeps_valid_utf8_in_mixed_input() {
+        let mut bytes = "caf\u{e9} \u{2014} ".as_bytes().to_vec();
+        bytes.extend_from_slice(b"\x96verbose  chatty\n");
+        let (text, encoding) = decode_help_text(&bytes);
+        assert_eq!(encoding, HelpEncoding::Windows1252);
+        assert_eq!(text, "caf\u{e9} \u{2014} \u{2013}verbose  chatty\n");
+    }
+
+    #[test]
+    fn decode_help_text_is_lossy_when_options_are_intact() {
+        let (text, encoding) = decode_help_text(b"-v  caf\xe9 mode\n");
+        assert_eq!(encoding, HelpEncoding::Lossy);
+        assert_eq!(text, "-v  caf\u{FFFD} mode\n");
+    }
+
+    /// Write an executable shell script into `dir` and return its path.
+    fn write_script(dir: &Path, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let path = dir.join("tool");
+        fs::write(&path, format!("#!/bin/sh\n{body}")).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    fn help_options() -> HelpOptions<'static> {
+        HelpOptions {
+            stream: HelpStream::Auto,
+            preamble: &[],
+            help_args: &[],
+            cwd: None,
+            usage_probe_arg: None,
+        }
+    }
+
+    #[test]
+    fn capture_help_uses_usage_error_when_help_lists_nothing() {
+        let dir = TempDir::new().unwrap();
+        let tool = write_script(
+            dir.path(),
+            "case \"$1\" in --bman-usage-probe-*) \
+             printf 'usage: tool [-a] [-b]\\n  -a  all\\n  -b  bee\\n' >&2; exit 2;; esac\n\
+             echo 'tool: no help available'\n",
+        );
+        let capture = capture_help(&tool, &help_options()).unwrap();
+        assert!(capture.flag.starts_with("--bman-usage-probe-"));
+        assert_eq!(capture.source, "stderr");
+        assert_eq!(count_help_options(&capture.bytes), 2);
+    }
+
+    #[test]
+    fn capture_help_uses_synopsis_only_usage_error() {
+        let dir = TempDir::new().unwrap();
+        let tool = write_script(
+            dir.path(),
+            "case \"$1\" in --bman-usage-probe-*) \
+             echo 'usage: tool [-abc] [--long=ARG] file...' >&2; exit 64;; esac\n\
+             echo 'tool: no help available'\n",
+        );
+        let capture = capture_help(&tool, &help_options()).unwrap();
+        assert!(capture.flag.starts_with("--bman-usage-probe-"));
+        assert_eq!(capture.source, "stderr");
+        assert_eq!(count_help_options(&capture.bytes), 4);
+    }
+
+    #[test]
+    fn capture_help_prefers_richer_usage_error_over_sparse_help() {
+        let dir = TempDir::new().unwrap();
+        let tool = write_script(
+            dir.path(),
+            "case \"$1\" in\n\
+             --help) printf 'tool: see usage\\n  -v  verbose\\n';;\n\
+             --bman-usage-probe-*) printf '  -a\\n  -b\\n  -v\\n' >&2; exit 2;;\n\
+             *) exit 0;;\n\
+             esac\n",
+        );
+        let capture = capture_help(&tool, &help_options()).unwrap();
+        assert!(capture.flag.starts_with("--bman-usage-probe-"));
+        assert_eq!(count_help_options(&capture.bytes), 3);
+    }
+
+    #[test]
+    fn capture_help_caps_oversized_output() {
+        let dir = TempDir::new().unwrap();
+        let tool = write_script(dir.path(), "yes '  -x  flag' | head -c 4194304\n");
+        let capture = capture_help(&tool, &help_options()).unwrap();
+        assert!(capture.truncated);
+        assert_eq!(capture.bytes.len(), HELP_MAX_BYTES);
+    }
+
+    #[test]
+    fn capture_help_keeps_help_when_usage_error_is_not_richer() {
+        let dir = TempDir::new().unwrap();
+        let tool = write_script(
+            dir.path(),
+            "case \"$1\" in\n\
+             --help) printf '  -v  verbose\\n';;\n\
+             --bman-usage-probe-*) printf 'usage: tool [-v]\\n' >&2; exit 2;;\n\
+             *) exit 0;;\n\
+             esac\n",
+        );
+        let capture = capture_help(&tool, &help_options()).unwrap();
+        assert_eq!(capture.flag, "--help");
+        assert!(capture.usage_probe.is_some());
+    }
+
+    fn select(stream: HelpStream, stdout: &str, stderr: &str) -> (String, &'static str) {
+        let (bytes, source) = select_help_text(stream, stdout.into(), stderr.into());
+        (String::from_utf8(bytes).unwrap(), source)
+    }
+
+    #[test]
+    fn select_help_text_auto_prefers_richer_stderr() {
+        let stderr = "usage: tool\n  -a  all\n  -b  bee\n";
+        assert_eq!(
+            select(HelpStream::Auto, "tool 1.0\n", stderr),
+            (stderr.to_string(), "stderr")
+        );
+    }
+
+    #[test]
+    fn select_help_text_auto_prefers_richer_stdout() {
+        let stdout = "  -a  all\n  -b  bee\n";
+        assert_eq!(
+            select(HelpStream::Auto, stdout, "  -a  all\n"),
+            (stdout.to_string(), "stdout")
+        );
+    }
+
+    #[test]
+    fn select_help_text_auto_tie_prefers_nonempty_stdout() {
+        assert_eq!(
+            select(HelpStream::Auto, "  -a  all\n", "  -b  bee\n"),
+            ("  -a  all\n".to_string(), "stdout")
+        );
+        assert_eq!(
+            select(HelpStream::Auto, "", "usage: tool\n"),
+            ("usage: tool\n".to_string(), "stderr")
+        );
+    }
+
+    #[test]
+    fn select_help_text_honors_forced_stream() {
+        let rich = "  -a  all\n  -b  bee\n";
+        assert_eq!(
+            select(HelpStream::Stdout, "", rich),
+            (String::new(), "stdout")
+        );
+        assert_eq!(
+            select(HelpStream::Stderr, rich, ""),
+            (String::new(), "stderr")
+        );
+    }
+
+    #[test]
+    fn decode_help_text_accepts_utf8() {
+        let (text, encoding) = decode_help_text("-a  \u{2014} all\n".as_bytes());
+        assert_eq!(encoding, HelpEncoding::Utf8);
+        assert_eq!(text, "-a  \u{2014} all\n");
+    }
+}
@@ -2,8 +2,10 @@
 
 mod binary;
 mod contract;
+mod doctor;
 mod evidence;
 mod fixture;
+mod gc;
 mod hashing;
 mod lm;
 mod limits;
@@ -11,41 +13,118 @@ mod paths;
 mod runner;
 mod scenario;
 mod transcript;
+mod verify;
 
-use anyhow::{Context, Result};
-use clap::Parser;
+use anyhow::{anyhow, Context, Result};
+use clap::{Parser, Subcommand};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use crate::binary::{hash_binary, resolve_binary, resolve_binary_input, BinaryTarget};
+use crate::binary::{
+    hash_binary, resolve_binary, resolve_binary_input, stat_binary, BinaryStat, BinaryTarget,
+};
 use crate::contract::{env_contract, EnvContract};
+use crate::doctor::{run_checks, DoctorStatus};
 use crate::evidence::{
-    create_evidence_dir, write_meta, ArtifactsMeta, BinaryMeta, ErrorReport, FixtureMeta, Meta,
-    Outcome, ResultMeta, SandboxMeta, TOOL_VERSION,
+    create_evidence_dir, write_artifact, write_help_meta, write_meta, ArtifactsMeta, BinaryMeta,
+    ErrorReport, FixtureMeta, HelpMeta, Meta, Outcome, ResultMeta, SandboxMeta, TOOL_VERSION,
+};
+use crate::fixture::{
+    fixture_root, load_fixture_catalog, prepare_fixture, read_fixture_catalog, validate_fixture,
 };
-use crate::fixture::{fixture_root, load_fixture_catalog, prepare_fixture, validate_fixture};
+use crate::gc::{gc_evidence, parse_duration, GcPolicy};
 use crate::hashing::sha256_hex;
 use crate::lm::{
-    build_prompt, capture_help, example_scenario_path, fixture_catalog_path, load_lm_command,
-    load_text, lm_schema_path, run_lm, scenario_schema_path,
+    build_prompt, capture_help, decode_help_text, example_scenario_path, fixture_catalog_path,
+    help_error_marker, load_lm_command, load_text, lm_schema_path, run_lm, scenario_schema_path,
+    HelpEncoding, HelpOptions, HelpStream, LmResponse, LmRetry,
+};
+use crate::runner::{
+    is_permission_denied, parse_ro_bind, run_direct, run_sandboxed, RoBind, RunOptions, SandboxKind,
+    SandboxTarget, SpawnRetry,
 };
-use crate::runner::{run_direct, run_sandboxed};
 use crate::scenario::{validate_scenario, Scenario};
 use crate::transcript::Transcript;
+use crate::verify::{verify_evidence, CheckStatus};
 
 const DEFAULT_OUT_DIR: &str = "out";
 const FIXTURES_DIR: &str = "fixtures";
 
-/// CLI arguments for the scenario runner.
+/// Top-level CLI: run a scenario by default, or manage evidence.
 #[derive(Parser, Debug)]
 #[command(
     name = "bman",
     version,
-    about = "Run or validate a single binary scenario in a sandbox"
+    about = "Run or validate a single binary scenario in a sandbox",
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
 )]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    args: Args,
+}
+
+/// Subcommands that operate on existing outputs.
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Manage evidence bundles under <out-dir>/evidence
+    #[command(subcommand)]
+    Evidence(EvidenceCommand),
+    /// Check the sandbox, LM backend, and repo assets a run needs
+    Doctor(DoctorArgs),
+    /// Inspect and validate fixtures without running a scenario
+    #[command(subcommand)]
+    Fixture(FixtureCommand),
+}
+
+/// Fixture maintenance commands.
+#[derive(Subcommand, Debug)]
+enum FixtureCommand {
+    /// Print catalog ids and descriptions
+    List(FixturesRootArgs),
+    /// Verify one fixture's manifest and tree
+    Validate(FixtureValidateArgs),
+    /// Check the catalog and verify every fixture it lists
+    CheckCatalog(FixturesRootArgs),
+}
+
+/// Fixture root shared by the fixture commands.
+#[derive(clap::Args, Debug)]
+struct FixturesRootArgs {
+    /// Fixture root containing catalog.json
+    #[arg(long, value_name = "DIR", default_value = FIXTURES_DIR)]
+    fixtures_root: PathBuf,
+}
+
+/// CLI arguments for `fixture validate`.
+#[derive(clap::Args, Debug)]
+struct FixtureValidateArgs {
+    /// Fixture id (e.g. fs/empty_dir)
+    id: String,
+
+    #[command(flatten)]
+    root: FixturesRootArgs,
+}
+
+/// Evidence maintenance commands.
+#[derive(Subcommand, Debug)]
+enum EvidenceCommand {
+    /// Remove old run directories, never touching the newest N
+    Gc(GcArgs),
+    /// Re-hash a bundle's files against meta.json; exits nonzero on mismatch
+    Verify(VerifyArgs),
+}
+
+/// CLI arguments for the scenario runner.
+#[derive(clap::Args, Debug)]
 struct Args {
     /// Binary name or path to inspect
-    binary: String,
+    #[arg(required = true)]
+    binary: Option<String>,
 
     /// Output directory root (evidence written under <dir>/evidence)
     #[arg(long, value_name = "DIR", default_value = DEFAULT_OUT_DIR)]
@@ -59,26 +138,242 @@ struct Args {
     #[arg(long)]
     direct: bool,
 
+    /// Expose a host path read-only in the sandbox (HOST:SANDBOX or PATH; repeatable)
+    #[arg(
+        long = "ro-bind",
+        value_name = "HOST:SANDBOX",
+        value_parser = parse_ro_bind,
+        conflicts_with = "direct"
+    )]
+    ro_binds: Vec<RoBind>,
+
     /// Emit a verbose transcript of the workflow
     #[arg(long)]
     verbose: bool,
+
+    /// LM invocations to attempt before giving up on a non-JSON or failed response
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    lm_attempts: u32,
+
+    /// Initial backoff between LM attempts, doubled per attempt
+    #[arg(long, value_name = "MS", default_value_t = 1000)]
+    lm_backoff_ms: u64,
+
+    /// Retries for transient spawn failures (ETXTBSY, EAGAIN)
+    #[arg(long, value_name = "N", default_value_t = 2)]
+    spawn_retries: u32,
+
+    /// Initial backoff between spawn retries, doubled per attempt
+    #[arg(long, value_name = "MS", default_value_t = 50)]
+    spawn_backoff_ms: u64,
+
+    /// Capture stdout and stderr interleaved into combined.txt (2>&1)
+    #[arg(long)]
+    combined_output: bool,
+
+    /// Skip scenario rlimits (for setuid or capability-restricted binaries)
+    #[arg(long)]
+    no_limits: bool,
+
+    /// Arg passed before the help flag and the scenario args (repeatable)
+    #[arg(long, value_name = "ARG", allow_hyphen_values = true)]
+    preamble: Vec<String>,
+
+    /// Exact help invocation, space-separated (e.g. "help options"); disables fallbacks
+    #[arg(
+        long,
+        value_name = "ARGS",
+        value_delimiter = ' ',
+        num_args = 1,
+        allow_hyphen_values = true
+    )]
+    help_args: Vec<String>,
+
+    /// Leave the detected option list out of the LM prompt (raw help only)
+    #[arg(long)]
+    no_prompt_options: bool,
+
+    /// Working directory for help capture (default: a fresh empty temp dir)
+    #[arg(long, value_name = "DIR")]
+    help_cwd: Option<PathBuf>,
+
+    /// Unknown option used to provoke a usage error (default: a random token)
+    #[arg(long, value_name = "ARG", allow_hyphen_values = true)]
+    usage_probe_arg: Option<String>,
+
+    /// Store stdout/stderr/combined gzipped (*.txt.gz); hashes stay uncompressed
+    #[arg(long)]
+    compress_artifacts: bool,
+
+    /// Which help command stream holds the help text
+    #[arg(long, value_enum, value_name = "STREAM", default_value_t = HelpStream::Auto)]
+    help_stream: HelpStream,
+}
+
+/// CLI arguments for `evidence gc`.
+#[derive(clap::Args, Debug)]
+struct GcArgs {
+    /// Output directory root (evidence read from <dir>/evidence)
+    #[arg(long, value_name = "DIR", default_value = DEFAULT_OUT_DIR)]
+    out_dir: PathBuf,
+
+    /// Always keep the newest N runs
+    #[arg(long, value_name = "N")]
+    keep_last: Option<usize>,
+
+    /// Only remove runs older than this age (e.g. 90s, 30m, 12h, 7d)
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    older_than: Option<Duration>,
+
+    /// Print what would be removed without deleting anything
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// CLI arguments for `doctor`.
+#[derive(clap::Args, Debug)]
+struct DoctorArgs {
+    /// Treat a missing sandbox as a warning (runs will use --direct)
+    #[arg(long)]
+    direct: bool,
+}
+
+/// CLI arguments for `evidence verify`.
+#[derive(clap::Args, Debug)]
+struct VerifyArgs {
+    /// Evidence run directory containing meta.json
+    #[arg(value_name = "DIR")]
+    dir: PathBuf,
 }
 
 fn main() -> Result<()> {
-    let args = Args::parse();
-    run(args)
+    let cli = Cli::parse();
+    match cli.command {
+        Some(Command::Evidence(EvidenceCommand::Gc(args))) => run_gc(args),
+        Some(Command::Evidence(EvidenceCommand::Verify(args))) => run_verify(args),
+        Some(Command::Doctor(args)) => run_doctor(args),
+        Some(Command::Fixture(command)) => run_fixture(command),
+        None => run(cli.args),
+    }
+}
+
+/// Prune evidence run directories and report what was removed.
+fn run_gc(args: GcArgs) -> Result<()> {
+    let policy = GcPolicy {
+        keep_last: args.keep_last,
+        older_than: args.older_than,
+        dry_run: args.dry_run,
+    };
+    let removed = gc_evidence(&args.out_dir, &policy)?;
+    let verb = if args.dry_run { "would remove" } else { "removed" };
+    for path in &removed {
+        println!("{verb}: {}", path.display());
+    }
+    println!("{verb} {} run(s)", removed.len());
+    Ok(())
+}
+
+/// Verify an evidence bundle and report each check.
+fn run_verify(args: VerifyArgs) -> Result<()> {
+    let checks = verify_evidence(&args.dir)?;
+    let mut failures = 0;
+    for check in &checks {
+        match &check.status {
+            CheckStatus::Ok => println!("ok: {}", check.name),
+            CheckStatus::NotStored => println!("skipped: {} (not present)", check.name),
+            CheckStatus::Mismatch { expected, actual } => println!(
+                "mismatch: {} expected={} actual={}",
+                check.name, expected, actual
+            ),
+            CheckStatus::Missing => println!("missing: {} (required)", check.name),
+            CheckStatus::Error(detail) => println!("error: {}: {detail}", check.name),
+        }
+        if check.status.is_failure() {
+            failures += 1;
+        }
+    }
+    if failures > 0 {
+        return Err(anyhow!(
+            "evidence verification failed: {failures} failing check(s) in {}",
+            args.dir.display()
+        ));
+    }
+    println!("verified {} check(s)", checks.len());
+    Ok(())
+}
+
+/// List, validate, or check fixtures and report problems.
+fn run_fixture(command: FixtureCommand) -> Result<()> {
+    match command {
+        FixtureCommand::List(args) => {
+            for entry in read_fixture_catalog(&args.fixtures_root)? {
+                println!("{}\t{}", entry.id, entry.description);
+            }
+            Ok(())
+        }
+        FixtureCommand::Validate(args) => {
+            let fixture_dir = fixture_root(&args.root.fixtures_root, &args.id)?;
+            let hash = validate_fixture(&fixture_dir)
+                .with_context(|| format!("fixture {}", args.id))?;
+            println!("ok: {} sha256={hash}", args.id);
+            Ok(())
+        }
+        FixtureCommand::CheckCatalog(args) => {
+            let root = &args.fixtures_root;
+            let mut ids: Vec<String> = load_fixture_catalog(root)?.into_iter().collect();
+            ids.sort();
+            let mut failures = 0;
+            for id in &ids {
+                match fixture_root(root, id).and_then(|dir| validate_fixture(&dir)) {
+                    Ok(hash) => println!("ok: {id} sha256={hash}"),
+                    Err(err) => {
+                        failures += 1;
+                        println!("fail: {id}: {err:#}");
+                    }
+                }
+            }
+            if failures > 0 {
+                return Err(anyhow!("{failures} of {} fixture(s) failed validation", ids.len()));
+            }
+            println!("checked {} fixture(s)", ids.len());
+            Ok(())
+        }
+    }
+}
+
+/// Check runtime prerequisites and print a pass/fail report.
+fn run_doctor(args: DoctorArgs) -> Result<()> {
+    let repo_root = std::env::current_dir().context("resolve repo root")?;
+    let mut failures = 0;
+    for check in run_checks(&repo_root, args.direct) {
+        match check.status {
+            DoctorStatus::Pass(detail) => println!("ok: {}: {detail}", check.name),
+            DoctorStatus::Warn(detail) => println!("warn: {}: {detail}", check.name),
+            DoctorStatus::Fail { detail, hint } => {
+                failures += 1;
+                println!("fail: {}: {detail}", check.name);
+                println!("  hint: {hint}");
+            }
+        }
+    }
+    if failures > 0 {
+        return Err(anyhow!("doctor found {failures} missing prerequisite(s)"));
+    }
+    println!("all prerequisites present");
+    Ok(())
 }
 
 /// Execute a single scenario and emit an evidence bundle.
 fn run(args: Args) -> Result<()> {
     let env = env_contract();
     let repo_root = std::env::current_dir().context("resolve repo root")?;
+    let binary_input = args.binary.as_deref().context("binary is required")?;
     let mut transcript = Transcript::new(args.verbose);
     transcript.note(format!(
         "start binary_input={} dry_run={} direct={}",
-        args.binary, args.dry_run, args.direct
+        binary_input, args.dry_run, args.direct
     ));
-    let target_binary = match resolve_binary_input(&args.binary) {
+    let target_binary = match resolve_binary_input(binary_input) {
         Ok(target) => target,
         Err(err) => {
             transcript.note(format!("resolve_target failed: {err}"));
@@ -89,6 +384,7 @@ fn run(args: Args) -> Result<()> {
                 "target binary invalid".to_string(),
                 vec![err.to_string()],
                 None,
+                None,
             )?;
             transcript.note(format!("evidence_dir {}", evidence_dir.display()));
             return Ok(());
@@ -100,7 +396,16 @@ fn run(args: Args) -> Result<()> {
         target_binary.resolved_path.display()
     ));
 
-    let help_capture = match capture_help(&target_binary.exec_path) {
+    let help_capture = match capture_help(
+        &target_binary.exec_path,
+        &HelpOptions {
+            stream: args.help_stream,
+            preamble: &args.preamble,
+            help_args: &args.help_args,
+            cwd: args.help_cwd.as_deref(),
+            usage_probe_arg: args.usage_probe_arg.as_deref(),
+        },
+    ) {
         Ok(capture) => capture,
         Err(err) => {
             transcript.note(format!("capture_help failed: {err}"));
@@ -111,17 +416,59 @@ fn run(args: Args) -> Result<()> {
                 "failed to capture help text".to_string(),
                 vec![err.to_string()],
                 None,
+                None,
             )?;
             transcript.note(format!("evidence_dir {}", evidence_dir.display()));
             return Ok(());
         }
     };
     transcript.note(format!(
-        "capture_help flag={} source={} bytes={}",
+        "capture_help flag={} source={} bytes={} truncated={} timed_out={}",
         help_capture.flag,
         help_capture.source,
-        help_capture.bytes.len()
+        help_capture.bytes.len(),
+        help_capture.truncated,
+        help_capture.timed_out
     ));
+    let (help_text, help_encoding) = decode_help_text(&help_capture.bytes);
+    if help_encoding != HelpEncoding::Utf8 {
+        transcript.note(format!(
+            "capture_help warning: help is not valid UTF-8 (decoded as {help_encoding:?}); \
+             prompt text may be degraded"
+        ));
+    }
+    let help_meta = HelpMeta {
+        flag: help_capture.flag.clone(),
+        stream: help_capture.source,
+        preamble: args.preamble.clone(),
+        cwd: args
+            .help_cwd
+            .as_ref()
+            .map_or_else(|| "temp".to_string(), |dir| dir.display().to_string()),
+        bytes: help_capture.bytes.len() as u64,
+        sha256: sha256_hex(&help_capture.bytes),
+        encoding: help_encoding,
+        truncated: help_capture.truncated,
+        timed_out: help_capture.timed_out,
+        usage_probe: help_capture.usage_probe.clone(),
+    };
+    if let Some(marker) = help_error_marker(&help_capture.bytes) {
+        transcript.note(format!("capture_help unavailable: matched {marker:?}"));
+        let evidence_dir = record_early_failure(
+            &args.out_dir,
+            &env,
+            "help_unavailable",
+            "help output is an error report, not help text".to_string(),
+            vec![format!(
+                "{} {} output matched {marker:?} and lists no options",
+                help_capture.flag, help_capture.source
+            )],
+            Some(&help_meta),
+            None,
+        )?;
+        transcript.note(format!("evidence_dir {}", evidence_dir.display()));
+        return Ok(());
+    }
 
     let schema_text = match load_text(&scenario_schema_path(&repo_root)) {
         Ok(text) => text,
@@ -133,6 +480,7 @@ fn run(args: Args) -> Result<()> {
                 "schema_asset_missing",
                 "failed to load scenario schema".to_string(),
                 vec![err.to_string()],
+                Some(&help_meta),
                 None,
             )?;
             transcript.note(format!("evidence_dir {}", evidence_dir.display()));
@@ -149,6 +497,7 @@ fn run(args: Args) -> Result<()> {
                 "schema_asset_missing",
                 "failed to load LM schema".to_string(),
                 vec![err.to_string()],
+                Some(&help_meta),
                 None,
             )?;
             transcript.note(format!("evidence_dir {}", evidence_dir.display()));
@@ -165,6 +514,7 @@ fn run(args: Args) -> Result<()> {
                 "catalog_asset_missing",
                 "failed to load fixture catalog".to_string(),
                 vec![err.to_string()],
+                Some(&help_meta),
                 None,
             )?;
             transcript.note(format!("evidence_dir {}", evidence_dir.display()));
@@ -181,13 +531,14 @@ fn run(args: Args) -> Result<()> {
         example_text.is_some()
     ));
 
-    let help_text = String::from_utf8_lossy(&help_capture.bytes);
     let prompt = build_prompt(
         &target_binary.exec_path,
         &help_text,
         &schema_text,
         &catalog_text,
         example_text.as_deref(),
+        &args.preamble,
+        !args.no_prompt_options,
     );
     transcript.note(format!("build_prompt bytes={}", prompt.len()));
     transcript.block("lm.prompt", &prompt);
@@ -202,24 +553,25 @@ fn run(args: Args) -> Result<()> {
                 "lm_command_invalid",
                 "failed to load LM command".to_string(),
                 vec![err.to_string()],
+                Some(&help_meta),
                 Some(&prompt),
             )?;
             transcript.note(format!("evidence_dir {}", evidence_dir.display()));
             return Ok(());
         }
     };
-    transcript.note(format!(
-        "run_lm program={} args={}",
-        lm_command
-            .argv
-            .first()
-            .map(|value| value.as_str())
-            .unwrap_or("<unknown>"),
-        lm_command.argv.len().saturating_sub(1)
-    ));
+    transcript.note(format!("run_lm {}", lm_command.describe()));
 
-    let response_bytes = match run_lm(&prompt, &lm_schema_text, &lm_command) {
-        Ok(bytes) => bytes,
+    let lm_retry = LmRetry {
+        attempts: args.lm_attempts,
+        backoff_ms: args.lm_backoff_ms,
+    };
+    let LmResponse {
+        raw: response_bytes,
+        json: response_json,
+        attempts: lm_attempts,
+    } = match run_lm(&prompt, &lm_schema_text, &lm_command, lm_retry) {
+        Ok(response) => response,
         Err(err) => {
             transcript.note(format!("run_lm failed: {err}"));
             let evidence_dir = record_early_failure(
@@ -228,35 +580,41 @@ fn run(args: Args) -> Result<()> {
                 "lm_failed",
                 "failed to obtain LM response".to_string(),
                 vec![err.to_string()],
+                Some(&help_meta),
                 Some(&prompt),
             )?;
             transcript.note(format!("evidence_dir {}", evidence_dir.display()));
             return Ok(());
         }
     };
-    transcript.note(format!("lm_response bytes={}", response_bytes.len()));
+    transcript.note(format!(
+        "lm_response bytes={} attempts={}",
+        response_bytes.len(),
+        lm_attempts
+    ));
     let response_text = String::from_utf8_lossy(&response_bytes);
     transcript.block("lm.response", &response_text);
 
     let ScenarioEnvelope {
         scenario,
         scenario_bytes,
-    } = match parse_scenario_response(&response_bytes) {
+    } = match parse_scenario_response(&response_json) {
         Ok(envelope) => envelope,
         Err(details) => {
             transcript.note(format!(
                 "parse_scenario failed: {}",
                 details.join("; ")
             ));
-            let scenario_hash = sha256_hex(&response_bytes);
+            let scenario_hash = sha256_hex(&response_json);
             let evidence_dir =
                 create_evidence_dir(&args.out_dir, Some(&scenario_hash), Some("lm_invalid"))?;
             transcript.note(format!("evidence_dir {}", evidence_dir.display()));
             if let Err(err) = write_lm_provenance(
                 &evidence_dir,
+                &help_meta,
                 &prompt,
                 &response_bytes,
-                &response_bytes,
+                &response_json,
             ) {
                 fail_schema(
                     &evidence_dir,
@@ -295,6 +653,7 @@ fn run(args: Args) -> Result<()> {
         create_evidence_dir(&args.out_dir, Some(&scenario_hash), Some(&scenario.scenario_id))?;
     if let Err(err) = write_lm_provenance(
         &evidence_dir,
+        &help_meta,
         &prompt,
         &response_bytes,
         &scenario_bytes,
@@ -380,6 +739,7 @@ fn run(args: Args) -> Result<()> {
         exec_binary,
         resolved_binary,
         binary_hash,
+        binary_stat,
     } = binary_validation;
 
     let fixture_dir = match fixture_root(&fixtures_root, &scenario.fixture.id) {
@@ -403,13 +763,12 @@ fn run(args: Args) -> Result<()> {
                         tool_version: TOOL_VERSION.to_string(),
                         scenario_sha256: Some(scenario_hash.clone()),
                         scenario_id: Some(scenario.scenario_id.clone()),
-                        binary: Some(BinaryMeta {
-                            path: scenario.binary.path.clone(),
-                            sha256: Some(binary_hash.clone()),
-                        }),
+                        binary: Some(binary_meta(&scenario, &binary_hash, &binary_stat)),
                         fixture: Some(FixtureMeta {
                             id: scenario.fixture.id.clone(),
                             sha256: None,
+                            mutated: None,
+                            changed_paths: Vec::new(),
                         }),
                         env: env.clone(),
                         limits: Some(scenario.limits),
@@ -456,13 +815,12 @@ fn run(args: Args) -> Result<()> {
             tool_version: TOOL_VERSION.to_string(),
             scenario_sha256: Some(scenario_hash),
             scenario_id: Some(scenario.scenario_id.clone()),
-            binary: Some(BinaryMeta {
-                path: scenario.binary.path.clone(),
-                sha256: Some(binary_hash),
-            }),
+            binary: Some(binary_meta(&scenario, &binary_hash, &binary_stat)),
             fixture: Some(FixtureMeta {
                 id: scenario.fixture.id.clone(),
                 sha256: Some(fixture_hash),
+                mutated: None,
+                changed_paths: Vec::new(),
             }),
             env,
             limits: Some(scenario.limits),
@@ -475,6 +833,7 @@ fn run(args: Args) -> Result<()> {
 
         write_meta(&evidence_dir, meta)?;
         transcript.note(format!("evidence_dir {}", evidence_dir.display()));
+        print_dry_run_plan(&scenario, &args);
         println!("evidence: {}", evidence_dir.display());
         return Ok(());
     }
@@ -494,13 +853,12 @@ fn run(args: Args) -> Result<()> {
                     tool_version: TOOL_VERSION.to_string(),
                     scenario_sha256: Some(scenario_hash),
                     scenario_id: Some(scenario.scenario_id.clone()),
-                    binary: Some(BinaryMeta {
-                        path: scenario.binary.path.clone(),
-                        sha256: Some(binary_hash.clone()),
-                    }),
+                    binary: Some(binary_meta(&scenario, &binary_hash, &binary_stat)),
                     fixture: Some(FixtureMeta {
                         id: scenario.fixture.id.clone(),
                         sha256: None,
+                        mutated: None,
+                        changed_paths: Vec::new(),
                     }),
                     env,
                     limits: Some(scenario.limits),
@@ -526,20 +884,36 @@ fn run(args: Args) -> Result<()> {
         prepared_fixture.fixture_hash
     ));
 
+    let run_options = RunOptions {
+        spawn_retry: SpawnRetry {
+            max_retries: args.spawn_retries,
+            backoff_ms: args.spawn_backoff_ms,
+        },
+        combined_output: args.combined_output,
+        no_limits: args.no_limits,
+        max_output_bytes: None,
+    };
+    let run_args = run_args(&args, &scenario);
     let run_result = if args.direct {
         run_direct(
             &exec_binary,
-            &scenario.args,
+            &run_args,
             &prepared_fixture.fixture_root,
             scenario.limits,
+            run_options,
         )
     } else {
         run_sandboxed(
-            &exec_binary,
-            &resolved_binary,
-            &scenario.args,
+            SandboxTarget {
+                exec_binary: &exec_binary,
+                binary_source: &resolved_binary,
+                binary_sha256: &binary_hash,
+                ro_binds: &args.ro_binds,
+            },
+            &run_args,
             &prepared_fixture.fixture_root,
             scenario.limits,
+            run_options,
         )
     };
 
@@ -553,26 +927,25 @@ fn run(args: Args) -> Result<()> {
                     tool_version: TOOL_VERSION.to_string(),
                     scenario_sha256: Some(scenario_hash),
                     scenario_id: Some(scenario.scenario_id.clone()),
-                    binary: Some(BinaryMeta {
-                        path: scenario.binary.path.clone(),
-                        sha256: Some(binary_hash.clone()),
-                    }),
+                    binary: Some(binary_meta(&scenario, &binary_hash, &binary_stat)),
                     fixture: Some(FixtureMeta {
                         id: scenario.fixture.id.clone(),
                         sha256: Some(prepared_fixture.fixture_hash.clone()),
+                        mutated: None,
+                        changed_paths: Vec::new(),
                     }),
                     env,
                     limits: Some(scenario.limits),
                     outcome: Outcome::SandboxFailed,
-                    error: Some(error_report("sandbox_failed", &err)),
+                    error: Some(error_report(run_error_code(&err), &err)),
                     result: None,
                     artifacts: None,
                     sandbox: Some(SandboxMeta {
-                        mode: if args.direct {
-                            "direct".to_string()
-                        } else {
-                            "bwrap".to_string()
-                        },
+                        mode: sandbox_mode(&args).to_string(),
+                        binary_sha256: None,
+                        preamble: args.preamble.clone(),
+                        ro_binds: args.ro_binds.clone(),
+                        no_limits: args.no_limits,
                     }),
                 },
             )?;
@@ -580,11 +953,12 @@ fn run(args: Args) -> Result<()> {
         }
     };
     transcript.note(format!(
-        "run_result exit_code={:?} timed_out={} wall_time_ms={} mode={}",
+        "run_result exit_code={:?} timed_out={} wall_time_ms={} spawn_retries={} mode={}",
         run_result.exit_code,
         run_result.timed_out,
         run_result.wall_time_ms,
-        if args.direct { "direct" } else { "bwrap" }
+        run_result.spawn_retries,
+        sandbox_mode(&args)
     ));
     if scenario.artifacts.capture_stdout {
         let stdout_text = String::from_utf8_lossy(&run_result.stdout);
@@ -594,18 +968,35 @@ fn run(args: Args) -> Result<()> {
         let stderr_text = String::from_utf8_lossy(&run_result.stderr);
         transcript.block("scenario.stderr", &stderr_text);
     }
+    if let Some(combined) = &run_result.combined {
+        let combined_text = String::from_utf8_lossy(combined);
+        transcript.block("scenario.combined", &combined_text);
+    }
 
     let stdout_hash = sha256_hex(&run_result.stdout);
     let stderr_hash = sha256_hex(&run_result.stderr);
+    let compress = args.compress_artifacts;
     if scenario.artifacts.capture_stdout {
-        fs::write(evidence_dir.join("stdout.txt"), &run_result.stdout)
-            .context("write stdout.txt")?;
+        write_artifact(&evidence_dir, "stdout.txt", &run_result.stdout, compress)?;
     }
     if scenario.artifacts.capture_stderr {
-        fs::write(evidence_dir.join("stderr.txt"), &run_result.stderr)
-            .context("write stderr.txt")?;
+        write_artifact(&evidence_dir, "stderr.txt", &run_result.stderr, compress)?;
+    }
+    if let Some(combined) = &run_result.combined {
+        write_artifact(&evidence_dir, "combined.txt", combined, compress)?;
     }
 
+    let changed_paths = match prepared_fixture.changed_paths() {
+        Ok(paths) => {
+            transcript.note(format!("fixture_changed_paths count={}", paths.len()));
+            Some(paths)
+        }
+        Err(err) => {
+            transcript.note(format!("fixture_changed_paths failed: {err:#}"));
+            None
+        }
+    };
+
     let outcome = if run_result.timed_out {
         Outcome::TimedOut
     } else {
@@ -616,13 +1007,12 @@ fn run(args: Args) -> Result<()> {
         tool_version: TOOL_VERSION.to_string(),
         scenario_sha256: Some(scenario_hash),
         scenario_id: Some(scenario.scenario_id.clone()),
-        binary: Some(BinaryMeta {
-            path: scenario.binary.path.clone(),
-            sha256: Some(binary_hash),
-        }),
+        binary: Some(binary_meta(&scenario, &binary_hash, &binary_stat)),
         fixture: Some(FixtureMeta {
             id: scenario.fixture.id.clone(),
-            sha256: Some(prepared_fixture.fixture_hash),
+            sha256: Some(prepared_fixture.fixture_hash.clone()),
+            mutated: changed_paths.as_ref().map(|paths| !paths.is_empty()),
+            changed_paths: changed_paths.unwrap_or_default(),
         }),
         env,
         limits: Some(scenario.limits),
@@ -632,19 +1022,23 @@ fn run(args: Args) -> Result<()> {
             exit_code: run_result.exit_code,
             timed_out: run_result.timed_out,
             wall_time_ms: run_result.wall_time_ms,
+            spawn_retries: run_result.spawn_retries,
         }),
         artifacts: Some(ArtifactsMeta {
             stdout_sha256: stdout_hash,
             stderr_sha256: stderr_hash,
             stdout_bytes: run_result.stdout.len() as u64,
             stderr_bytes: run_result.stderr.len() as u64,
+            combined_sha256: run_result.combined.as_deref().map(sha256_hex),
+            combined_bytes: run_result.combined.as_ref().map(|bytes| bytes.len() as u64),
+            compressed: compress,
         }),
         sandbox: Some(SandboxMeta {
-            mode: if args.direct {
-                "direct".to_string()
-            } else {
-                "bwrap".to_string()
-            },
+            mode: sandbox_mode(&args).to_string(),
+            binary_sha256: run_result.staged_binary_sha256.clone(),
+            preamble: args.preamble.clone(),
+            ro_binds: args.ro_binds.clone(),
+            no_limits: args.no_limits,
         }),
     };
 
@@ -661,9 +1055,13 @@ fn record_early_failure(
     code: &str,
     message: String,
     details: Vec<String>,
+    help: Option<&HelpMeta>,
     prompt: Option<&str>,
 ) -> Result<PathBuf> {
     let evidence_dir = create_evidence_dir(out_dir, None, Some(code))?;
+    if let Some(help) = help {
+        write_help_meta(&evidence_dir, help)?;
+    }
     if let Some(prompt) = prompt {
         fs::write(evidence_dir.join("lm.prompt.txt"), prompt.as_bytes())
             .context("write lm.prompt.txt")?;
@@ -674,10 +1072,12 @@ fn record_early_failure(
 
 fn write_lm_provenance(
     evidence_dir: &Path,
+    help: &HelpMeta,
     prompt: &str,
     response: &[u8],
     scenario_bytes: &[u8],
 ) -> Result<()> {
+    write_help_meta(evidence_dir, help)?;
     fs::write(evidence_dir.join("lm.prompt.txt"), prompt.as_bytes())
         .context("write lm.prompt.txt")?;
     fs::write(evidence_dir.join("lm.response.json"), response)
@@ -696,6 +1096,7 @@ struct BinaryValidation {
     exec_binary: PathBuf,
     resolved_binary: PathBuf,
     binary_hash: String,
+    binary_stat: BinaryStat,
 }
 
 fn parse_scenario_response(response: &[u8]) -> Result<ScenarioEnvelope, Vec<String>> {
@@ -883,10 +1284,32 @@ fn validate_binary(
     };
     transcript.note(format!("binary_hash {}", binary_hash));
 
+    let binary_stat = match stat_binary(&resolved_binary) {
+        Ok(stat) => stat,
+        Err(err) => {
+            transcript.note(format!("stat binary failed: {err}"));
+            let message = format!("failed to stat binary: {err}");
+            return record_binary_failure(
+                args,
+                env,
+                evidence_dir,
+                scenario_hash,
+                scenario,
+                "binary_invalid",
+                message,
+            );
+        }
+    };
+    transcript.note(format!(
+        "binary_stat size_bytes={} mtime={:?}",
+        binary_stat.size_bytes, binary_stat.mtime
+    ));
+
     Ok(Some(BinaryValidation {
         exec_binary,
         resolved_binary,
         binary_hash,
+        binary_stat,
     }))
 }
 
@@ -931,6 +1354,8 @@ fn write_binary_missing(
             binary: Some(BinaryMeta {
                 path: scenario.binary.path.clone(),
                 sha256: None,
+                size_bytes: None,
+                mtime: None,
             }),
             fixture: None,
             env: env.clone(),
@@ -948,6 +1373,54 @@ fn write_binary_missing(
     )
 }
 
+/// Print the invocation a real run would perform, without executing it.
+fn print_dry_run_plan(scenario: &Scenario, args: &Args) {
+    let argv =
+        serde_json::to_string(&run_args(args, scenario)).unwrap_or_else(|_| "[]".to_string());
+    println!("plan: {} {argv}", scenario.binary.path);
+    println!(
+        "plan: fixture={} sandbox={} wall_time_ms={} cpu_time_ms={} memory_kb={}",
+        scenario.fixture.id,
+        sandbox_mode(args),
+        scenario.limits.wall_time_ms,
+        scenario.limits.cpu_time_ms,
+        scenario.limits.memory_kb
+    );
+}
+
+/// Args actually passed to the binary: the preamble, then the scenario args.
+fn run_args(args: &Args, scenario: &Scenario) -> Vec<String> {
+    let mut run_args = args.preamble.clone();
+    run_args.extend(scenario.args.iter().cloned());
+    run_args
+}
+
+fn binary_meta(scenario: &Scenario, binary_hash: &str, stat: &BinaryStat) -> BinaryMeta {
+    BinaryMeta {
+        path: scenario.binary.path.clone(),
+        sha256: Some(binary_hash.to_string()),
+        size_bytes: Some(stat.size_bytes),
+        mtime: stat.mtime,
+    }
+}
+
+/// Execution mode recorded in `SandboxMeta` (`direct`, or the host sandbox).
+fn sandbox_mode(args: &Args) -> &'static str {
+    if args.direct {
+        return "direct";
+    }
+    SandboxKind::host().map(SandboxKind::name).unwrap_or("none")
+}
+
+/// Error code for a failed run; permission-denied spawns get their own code.
+fn run_error_code(err: &anyhow::Error) -> &'static str {
+    if is_permission_denied(err) {
+        "spawn_permission_denied"
+    } else {
+        "sandbox_failed"
+    }
+}
+
 fn error_report(code: &str, err: &anyhow::Error) -> ErrorReport {
     let details = err.chain().skip(1).map(|cause| cause.to_string()).collect();
     ErrorReport {
@@ -1,15 +1,21 @@
 //! Execution paths for scenarios (direct or sandboxed).
+//!
+//! Sandboxing is selected at runtime: bwrap on Linux, `sandbox-exec` on macOS.
 
 use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
 use std::fs;
-use std::io;
+use std::io::{self, Read};
 use std::os::unix::process::CommandExt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::thread;
 use std::time::{Duration, Instant};
 
-use crate::contract::{apply_env_contract, ENV_LC_ALL, ENV_PATH, ENV_TERM, ENV_TZ};
+use crate::contract::{
+    apply_env_contract, ENV_CLICOLOR, ENV_LC_ALL, ENV_NO_COLOR, ENV_PATH, ENV_TERM, ENV_TZ,
+};
+use crate::hashing::sha256_file;
 use crate::limits::configure_child;
 use crate::scenario::ScenarioLimits;
 
@@ -20,6 +26,91 @@ pub(crate) struct RunResult {
     pub(crate) wall_time_ms: u64,
     pub(crate) stdout: Vec<u8>,
     pub(crate) stderr: Vec<u8>,
+    /// stdout and stderr interleaved in arrival order (combined mode only).
+    pub(crate) combined: Option<Vec<u8>>,
+    pub(crate) spawn_retries: u32,
+    /// SHA-256 of the binary copy executed inside the sandbox, once verified.
+    pub(crate) staged_binary_sha256: Option<String>,
+}
+
+/// Binary to execute inside a sandbox.
+///
+/// `exec_binary` preserves argv[0] semantics, while `binary_source` is copied
+/// into the run root to provide the executable bytes; the copy must hash to
+/// `binary_sha256` before it is executed. `ro_binds` are extra host paths
+/// exposed read-only.
+#[derive(Copy, Clone)]
+pub(crate) struct SandboxTarget<'a> {
+    pub(crate) exec_binary: &'a Path,
+    pub(crate) binary_source: &'a Path,
+    pub(crate) binary_sha256: &'a str,
+    pub(crate) ro_binds: &'a [RoBind],
+}
+
+/// Extra read-only mount from `--ro-bind HOST:SANDBOX`.
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct RoBind {
+    pub(crate) host: PathBuf,
+    pub(crate) sandbox: PathBuf,
+}
+
+/// Sandbox paths the runner manages itself; extra binds may not shadow them.
+const RESERVED_SANDBOX_PATHS: &[&str] = &["/bin", "/work", "/proc", "/dev", "/tmp", "/nix"];
+
+/// Parse `HOST:SANDBOX` (or a bare `PATH` for both); both must be absolute
+/// and the host path must exist.
+pub(crate) fn parse_ro_bind(value: &str) -> Result<RoBind, String> {
+    let (host, sandbox) = value.split_once(':').unwrap_or((value, value));
+    let host = PathBuf::from(host);
+    let sandbox = PathBuf::from(sandbox);
+    if !host.is_absolute() || !sandbox.is_absolute() {
+        return Err(format!("--ro-bind paths must be absolute: {value}"));
+    }
+    if !host.exists() {
+        return Err(format!("--ro-bind host path does not exist: {}", host.display()));
+    }
+    if sandbox == Path::new("/")
+        || RESERVED_SANDBOX_PATHS
+            .iter()
+            .any(|reserved| sandbox.starts_with(reserved))
+    {
+        return Err(format!(
+            "--ro-bind may not target / or {}: {}",
+            RESERVED_SANDBOX_PATHS.join(", "),
+            sandbox.display()
+        ));
+    }
+    Ok(RoBind { host, sandbox })
+}
+
+/// Execution options shared by the direct and sandboxed paths.
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct RunOptions {
+    pub(crate) spawn_retry: SpawnRetry,
+    /// Send stdout and stderr to one pipe (`2>&1`) instead of capturing separately.
+    pub(crate) combined_output: bool,
+    /// Skip rlimits in the child (`--no-limits`); wall time is still enforced.
+    pub(crate) no_limits: bool,
+    /// Keep at most this many bytes (plus one, so callers can detect overflow)
+    /// per captured stream; the rest is read and discarded so the child never
+    /// blocks on a full pipe.
+    pub(crate) max_output_bytes: Option<usize>,
+}
+
+/// Bounded retry policy for transient spawn failures.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SpawnRetry {
+    pub(crate) max_retries: u32,
+    pub(crate) backoff_ms: u64,
+}
+
+impl Default for SpawnRetry {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            backoff_ms: 50,
+        }
+    }
 }
 
 /// Execute the target binary directly on the host (debug mode).
@@ -30,45 +121,81 @@ pub(crate) fn run_direct(
     args: &[String],
     cwd: &Path,
     limits: ScenarioLimits,
+    options: RunOptions,
 ) -> Result<RunResult> {
     let mut command = Command::new(binary);
     command.args(args);
     command.current_dir(cwd);
     apply_env_contract(&mut command);
-    run_command(command, limits)
+    run_command(command, limits, options)
 }
 
-/// Execute the target binary inside a rootless bwrap sandbox.
-///
-/// `exec_binary` preserves argv[0] semantics, while `binary_source` is copied
-/// into the sandbox to provide the executable bytes.
+/// Sandbox implementations selectable at runtime.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum SandboxKind {
+    Bwrap,
+    SandboxExec,
+}
+
+impl SandboxKind {
+    /// Sandbox implementation for the current host OS, if any.
+    pub(crate) fn host() -> Option<Self> {
+        match std::env::consts::OS {
+            "linux" => Some(SandboxKind::Bwrap),
+            "macos" => Some(SandboxKind::SandboxExec),
+            _ => None,
+        }
+    }
+
+    /// Mode name recorded in `SandboxMeta`.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            SandboxKind::Bwrap => "bwrap",
+            SandboxKind::SandboxExec => "sandbox-exec",
+        }
+    }
+}
+
+/// Execute the target binary inside the host's sandbox (bwrap or sandbox-exec).
 pub(crate) fn run_sandboxed(
-    exec_binary: &Path,
-    binary_source: &Path,
+    target: SandboxTarget<'_>,
     args: &[String],
     fixture_root: &Path,
     limits: ScenarioLimits,
+    options: RunOptions,
 ) -> Result<RunResult> {
-    if !Path::new("/nix/store").exists() {
-        return Err(anyhow!("expected /nix/store for sandbox mounts"));
+    match SandboxKind::host() {
+        Some(SandboxKind::Bwrap) => {
+            run_bwrap(target, args, fixture_root, limits, options)
+        }
+        Some(SandboxKind::SandboxExec) => {
+            run_sandbox_exec(target, args, fixture_root, limits, options)
+        }
+        None => Err(anyhow!(
+            "no sandbox available on {}; rerun with --direct to execute unsandboxed",
+            std::env::consts::OS
+        )),
     }
+}
 
-    let bwrap = Path::new("bwrap");
-    let binary_name = exec_binary
-        .file_name()
-        .and_then(|name| name.to_str())
-        .unwrap_or("binary");
-
-    let run_root = fixture_root
-        .parent()
-        .ok_or_else(|| anyhow!("fixture root has no parent"))?;
-    let bin_root = run_root.join("bin");
-    fs::create_dir_all(&bin_root).context("create bin dir")?;
-    let sandbox_binary = bin_root.join(binary_name);
-    fs::copy(binary_source, &sandbox_binary).context("copy binary into run root")?;
-    let metadata = fs::metadata(binary_source).context("stat binary for permissions")?;
-    fs::set_permissions(&sandbox_binary, metadata.permissions())
-        .context("apply binary permissions")?;
+/// Execute the target binary inside a rootless bwrap sandbox.
+fn run_bwrap(
+    target: SandboxTarget<'_>,
+    args: &[String],
+    fixture_root: &Path,
+    limits: ScenarioLimits,
+    options: RunOptions,
+) -> Result<RunResult> {
+    if !Path::new("/nix/store").exists() {
+        return Err(anyhow!(
+            "bwrap sandbox expects /nix/store for mounts; no sandbox available \
+             (rerun with --direct to execute unsandboxed)"
+        ));
+    }
+    let bwrap = find_in_path("bwrap").ok_or_else(|| {
+        anyhow!("bwrap not found in PATH; no sandbox available (rerun with --direct)")
+    })?;
+    let (bin_root, binary_name) = stage_binary(target, fixture_root)?;
 
     let mut command = Command::new(bwrap);
     command.arg("--die-with-parent");
@@ -98,6 +225,9 @@ pub(crate) fn run_sandboxed(
     command.arg("--ro-bind").arg("/nix/store").arg("/nix/store");
     command.arg("--ro-bind").arg(&bin_root).arg("/bin");
     command.arg("--bind").arg(fixture_root).arg("/work");
+    for bind in target.ro_binds {
+        command.arg("--ro-bind").arg(&bind.host).arg(&bind.sandbox);
+    }
     command.arg("--chdir");
     command.arg("/work");
     command.arg("--clearenv");
@@ -111,38 +241,204 @@ pub(crate) fn run_sandboxed(
     command.arg("TERM");
     command.arg(ENV_TERM);
     command.arg("--setenv");
+    command.arg("NO_COLOR");
+    command.arg(ENV_NO_COLOR);
+    command.arg("--setenv");
+    command.arg("CLICOLOR");
+    command.arg(ENV_CLICOLOR);
+    command.arg("--setenv");
     command.arg("PATH");
     command.arg(ENV_PATH);
     command.arg("--");
     command.arg(format!("/bin/{binary_name}"));
     command.args(args);
 
-    run_command(command, limits)
+    let mut result = run_command(command, limits, options)?;
+    result.staged_binary_sha256 = Some(target.binary_sha256.to_string());
+    Ok(result)
+}
+
+/// Execute the target binary under macOS `sandbox-exec` with a generated
+/// profile: no network, read-only system libraries and the staged binary, and
+/// read-write access to the fixture root only.
+fn run_sandbox_exec(
+    target: SandboxTarget<'_>,
+    args: &[String],
+    fixture_root: &Path,
+    limits: ScenarioLimits,
+    options: RunOptions,
+) -> Result<RunResult> {
+    let sandbox_exec = find_in_path("sandbox-exec").ok_or_else(|| {
+        anyhow!("sandbox-exec not found in PATH; no sandbox available (rerun with --direct)")
+    })?;
+    // sandbox-exec cannot remap paths, so binds only grant read access in place.
+    let mut read_roots = Vec::new();
+    for bind in target.ro_binds {
+        if bind.host != bind.sandbox {
+            return Err(anyhow!(
+                "sandbox-exec cannot remap --ro-bind {} to {}; use the same path",
+                bind.host.display(),
+                bind.sandbox.display()
+            ));
+        }
+        read_roots.push(fs::canonicalize(&bind.host).context("resolve --ro-bind path")?);
+    }
+    let (bin_root, binary_name) = stage_binary(target, fixture_root)?;
+    // Profiles match on real paths (e.g. /private/var rather than /var).
+    let sandbox_binary = fs::canonicalize(bin_root.join(&binary_name))
+        .context("resolve staged binary")?;
+    let work_root = fs::canonicalize(fixture_root).context("resolve fixture root")?;
+    let profile = sandbox_exec_profile(&sandbox_binary, &work_root, &read_roots);
+
+    let mut command = Command::new(sandbox_exec);
+    command.arg("-p").arg(profile);
+    command.arg(&sandbox_binary);
+    command.args(args);
+    command.current_dir(&work_root);
+    apply_env_contract(&mut command);
+
+    let mut result = run_command(command, limits, options)?;
+    result.staged_binary_sha256 = Some(target.binary_sha256.to_string());
+    Ok(result)
 }
 
-fn run_command(mut command: Command, limits: ScenarioLimits) -> Result<RunResult> {
+fn sandbox_exec_profile(binary: &Path, work_root: &Path, read_roots: &[PathBuf]) -> String {
+    let binary = profile_string(binary);
+    let work_root = profile_string(work_root);
+    let extra_reads: String = read_roots
+        .iter()
+        .map(|root| format!("(allow file-read* (subpath {}))\n", profile_string(root)))
+        .collect();
+    format!(
+        "(version 1)\n\
+         (deny default)\n\
+         (deny network*)\n\
+         (allow process-exec (literal {binary}))\n\
+         (allow process-fork)\n\
+         (allow signal (target self))\n\
+         (allow sysctl-read)\n\
+         (allow file-read-metadata)\n\
+         (allow file-read* (literal {binary}) (subpath \"/usr/lib\") \
+         (subpath \"/usr/share\") (subpath \"/System/Library\") \
+         (subpath \"/private/var/db/dyld\") (literal \"/dev/urandom\") \
+         (literal \"/dev/random\"))\n\
+         (allow file-read* file-write* (subpath {work_root}) (literal \"/dev/null\"))\n\
+         {extra_reads}"
+    )
+}
+
+/// Quote a path as a sandbox profile string literal.
+fn profile_string(path: &Path) -> String {
+    let raw = path.to_string_lossy();
+    let escaped = raw.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+/// Copy the binary into `<run_root>/bin/` and verify the copy's hash so the
+/// sandbox executes exactly the bytes that were hashed.
+fn stage_binary(target: SandboxTarget<'_>, fixture_root: &Path) -> Result<(PathBuf, String)> {
+    let binary_name = target
+        .exec_binary
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("binary")
+        .to_string();
+    let run_root = fixture_root
+        .parent()
+        .ok_or_else(|| anyhow!("fixture root has no parent"))?;
+    let bin_root = run_root.join("bin");
+    fs::create_dir_all(&bin_root).context("create bin dir")?;
+    let sandbox_binary = bin_root.join(&binary_name);
+    fs::copy(target.binary_source, &sandbox_binary).context("copy binary into run root")?;
+    let metadata = fs::metadata(target.binary_source).context("stat binary for permissions")?;
+    fs::set_permissions(&sandbox_binary, metadata.permissions())
+        .context("apply binary permissions")?;
+    let staged_hash = sha256_file(&sandbox_binary).context("hash staged binary")?;
+    if staged_hash != target.binary_sha256 {
+        return Err(anyhow!(
+            "staged binary hash mismatch: expected {}, copied {}",
+            target.binary_sha256,
+            staged_hash
+        ));
+    }
+    Ok((bin_root, binary_name))
+}
+
+/// Locate an executable by name on `PATH`.
+pub(crate) fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+fn run_command(
+    mut command: Command,
+    limits: ScenarioLimits,
+    options: RunOptions,
+) -> Result<RunResult> {
+    let retry = options.spawn_retry;
+    let max_bytes = options.max_output_bytes;
     command.stdin(Stdio::null());
-    command.stdout(Stdio::piped());
-    command.stderr(Stdio::piped());
+    let combined_reader = if options.combined_output {
+        let (reader, writer) = io::pipe().context("create combined output pipe")?;
+        let writer_err = writer.try_clone().context("clone combined output pipe")?;
+        command.stdout(writer);
+        command.stderr(writer_err);
+        Some(reader)
+    } else {
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+        None
+    };
 
     let limits_copy = limits;
+    let rlimits = !options.no_limits;
     unsafe {
-        command.pre_exec(move || configure_child(limits_copy));
+        command.pre_exec(move || configure_child(limits_copy, rlimits));
     }
 
-    let mut child = command.spawn().context("spawn command")?;
+    let mut spawn_retries = 0;
+    let mut child = loop {
+        match command.spawn() {
+            Ok(child) => break child,
+            Err(err) if spawn_retries < retry.max_retries && is_transient_spawn_error(&err) => {
+                let backoff = retry.backoff_ms.saturating_mul(1 << spawn_retries.min(16));
+                spawn_retries += 1;
+                thread::sleep(Duration::from_millis(backoff));
+            }
+            Err(err) if is_permission_spawn_error(&err) => {
+                return Err(SpawnPermissionDenied(err).into());
+            }
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("spawn command (after {spawn_retries} retries)")
+                })
+            }
+        }
+    };
+    // Drop the parent's copies of the combined pipe's write ends so the
+    // reader sees EOF once the child exits.
+    drop(command);
     let pid = child.id();
-    let stdout = child
-        .stdout
-        .take()
-        .ok_or_else(|| anyhow!("stdout not captured"))?;
-    let stderr = child
-        .stderr
-        .take()
-        .ok_or_else(|| anyhow!("stderr not captured"))?;
-
-    let stdout_handle = thread::spawn(move || read_all(stdout));
-    let stderr_handle = thread::spawn(move || read_all(stderr));
+    let (stdout_handle, stderr_handle, combined_handle) = match combined_reader {
+        Some(reader) => (None, None, Some(thread::spawn(move || read_all(reader, max_bytes)))),
+        None => {
+            let stdout = child
+                .stdout
+                .take()
+                .ok_or_else(|| anyhow!("stdout not captured"))?;
+            let stderr = child
+                .stderr
+                .take()
+                .ok_or_else(|| anyhow!("stderr not captured"))?;
+            (
+                Some(thread::spawn(move || read_all(stdout, max_bytes))),
+                Some(thread::spawn(move || read_all(stderr, max_bytes))),
+                None,
+            )
+        }
+    };
 
     let timeout = Duration::from_millis(limits.wall_time_ms);
     let start = Instant::now();
@@ -160,8 +456,12 @@ fn run_command(mut command: Command, limits: ScenarioLimits) -> Result<RunResult
     };
 
     let wall_time_ms = start.elapsed().as_millis() as u64;
-    let stdout = stdout_handle.join().unwrap_or_else(|_| Ok(Vec::new()))?;
-    let stderr = stderr_handle.join().unwrap_or_else(|_| Ok(Vec::new()))?;
+    let stdout = join_output(stdout_handle)?;
+    let stderr = join_output(stderr_handle)?;
+    let combined = match combined_handle {
+        Some(handle) => Some(join_output(Some(handle))?),
+        None => None,
+    };
     let exit_code = exit_status.code();
 
     Ok(RunResult {
@@ -170,17 +470,175 @@ fn run_command(mut command: Command, limits: ScenarioLimits) -> Result<RunResult
         wall_time_ms,
         stdout,
         stderr,
+        combined,
+        spawn_retries,
+        staged_binary_sha256: None,
     })
 }
 
+fn join_output(handle: Option<thread::JoinHandle<io::Result<Vec<u8>>>>) -> io::Result<Vec<u8>> {
+    match handle {
+        Some(handle) => handle.join().unwrap_or_else(|_| Ok(Vec::new())),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Spawn errors worth retrying: a busy executable (`ETXTBSY`, e.g. still being
+/// written) or temporary resource exhaustion (`EAGAIN`, `EINTR`). Missing
+/// binaries and permission errors are never retried.
+fn is_transient_spawn_error(err: &io::Error) -> bool {
+    match err.kind() {
+        io::ErrorKind::ExecutableFileBusy
+        | io::ErrorKind::WouldBlock
+        | io::ErrorKind::Interrupted => true,
+        io::ErrorKind::NotFound | io::ErrorKind::PermissionDenied => false,
+        _ => matches!(
+            err.raw_os_error(),
+            Some(libc::ETXTBSY) | Some(libc::EAGAIN) | Some(libc::EINTR)
+        ),
+    }
+}
+
+/// `EPERM`/`EACCES` at spawn, including a failed `setrlimit` in the child.
+fn is_permission_spawn_error(err: &io::Error) -> bool {
+    err.kind() == io::ErrorKind::PermissionDenied
+        || matches!(err.raw_os_error(), Some(libc::EPERM) | Some(libc::EACCES))
+}
+
+/// `Command::spawn` failed with `EPERM`/`EACCES`.
+///
+/// Raised only at the spawn site, so permission errors from staging, fixture
+/// copies, or evidence writes are not mistaken for it.
+#[derive(Debug)]
+struct SpawnPermissionDenied(io::Error);
+
+impl std::fmt::Display for SpawnPermissionDenied {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "spawn command: permission denied; the binary may be setuid, \
+             capability-restricted, or refuse rlimits (try --no-limits)"
+        )
+    }
+}
+
+impl std::error::Error for SpawnPermissionDenied {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// Whether a run error came from a permission-denied `Command::spawn`.
+pub(crate) fn is_permission_denied(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| cause.is::<SpawnPermissionDenied>())
+}
+
 fn kill_process_group(pid: u32) {
     unsafe {
         libc::kill(-(pid as i32), libc::SIGKILL);
     }
 }
 
-fn read_all(mut reader: impl io::Read) -> io::Result<Vec<u8>> {
+fn read_all(mut reader: impl io::Read, max_bytes: Option<usize>) -> io::Result<Vec<u8>> {
     let mut buf = Vec::new();
-    reader.read_to_end(&mut buf)?;
+    let Some(max_bytes) = max_bytes else {
+        reader.read_to_end(&mut buf)?;
+        return Ok(buf);
+    };
+    let limit = u64::try_from(max_bytes).unwrap_or(u64::MAX).saturating_add(1);
+    (&mut reader).take(limit).read_to_end(&mut buf)?;
+    io::copy(&mut reader, &mut io::sink())?;
     Ok(buf)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::DEFAULT_STACK_KB;
+
+    #[test]
+    fn transient_spawn_errors_are_retried() {
+        for errno in [libc::ETXTBSY, libc::EAGAIN, libc::EINTR] {
+            assert!(is_transient_spawn_error(&io::Error::from_raw_os_error(errno)), "{errno}");
+        }
+        for errno in [libc::ENOENT, libc::EACCES, libc::EPERM, libc::ENOEXEC] {
+            assert!(!is_transient_spawn_error(&io::Error::from_raw_os_error(errno)), "{errno}");
+        }
+    }
+
+    #[test]
+    fn permission_denied_is_only_classified_at_spawn() {
+        let io_denied = || io::Error::from_raw_os_error(libc::EACCES);
+        let staging = Err::<(), _>(io_denied()).context("copy binary into run root");
+        assert!(!is_permission_denied(&staging.unwrap_err()));
+
+        let spawn = anyhow::Error::from(SpawnPermissionDenied(io_denied())).context("run");
+        assert!(is_permission_denied(&spawn));
+
+        assert!(is_permission_spawn_error(&io::Error::from_raw_os_error(libc::EPERM)));
+        assert!(!is_permission_spawn_error(&io::Error::from_raw_os_error(libc::ETXTBSY)));
+    }
+
+    #[test]
+    fn run_direct_classifies_non_executable_binary() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let script = dir.path().join("tool");
+        fs::write(&script, "#!/bin/sh\n").unwrap();
+        let limits = ScenarioLimits {
+            wall_time_ms: 1000,
+            cpu_time_ms: 1000,
+            memory_kb: 65536,
+            file_size_kb: 1024,
+            max_processes: None,
+            open_files: 64,
+            stack_kb: None,
+        };
+        let err = run_direct(&script, &[], dir.path(), limits, RunOptions::default())
+            .err()
+            .expect("spawning a 0644 file must fail");
+        assert!(is_permission_denied(&err), "{err:#}");
+    }
+
+    #[test]
+    fn run_direct_applies_default_stack_limit() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let limits = ScenarioLimits {
+            wall_time_ms: 5000,
+            cpu_time_ms: 5000,
+            memory_kb: 262_144,
+            file_size_kb: 1024,
+            max_processes: None,
+            open_files: 64,
+            stack_kb: None,
+        };
+        let args = ["-c".to_string(), "ulimit -s; ulimit -Hs".to_string()];
+        let result =
+            run_direct(Path::new("/bin/sh"), &args, dir.path(), limits, RunOptions::default())
+                .unwrap();
+        assert_eq!(result.exit_code, Some(0), "{}", String::from_utf8_lossy(&result.stderr));
+        let stdout = String::from_utf8(result.stdout).unwrap();
+        let kib: Vec<u64> = stdout.lines().map(|line| line.parse().unwrap()).collect();
+        assert_eq!(kib.len(), 2, "{stdout}");
+        assert_eq!(kib[1], DEFAULT_STACK_KB);
+        assert!(kib[0] <= DEFAULT_STACK_KB, "{stdout}");
+    }
+
+    #[test]
+    fn read_all_keeps_one_byte_past_the_cap_and_drains_the_rest() {
+        let mut input = io::Cursor::new(vec![7u8; 100]);
+        assert_eq!(read_all(&mut input, Some(10)).unwrap().len(), 11);
+        assert_eq!(input.position(), 100);
+        assert_eq!(read_all(io::Cursor::new(vec![7u8; 5]), Some(10)).unwrap().len(), 5);
+        assert_eq!(read_all(io::Cursor::new(vec![7u8; 100]), None).unwrap().len(), 100);
+    }
+
+    #[test]
+    fn parse_ro_bind_rejects_reserved_targets() {
+        for target in ["/", "/nix", "/nix/store/abc", "/bin/tool", "/work"] {
+            let err = parse_ro_bind(&format!("/tmp:{target}")).expect_err(target);
+            assert!(err.contains("may not target"), "{target}: {err}");
+        }
+        let bind = parse_ro_bind("/tmp:/opt/data").unwrap();
+        assert_eq!(bind.sandbox, Path::new("/opt/data"));
+    }
+}
@@ -12,6 +12,17 @@ pub(crate) const MAX_CPU_TIME_MS: u64 = 30_000;
 pub(crate) const MAX_MEMORY_KB: u64 = 262_144;
 /// Maximum file size accepted by the runner.
 pub(crate) const MAX_FILE_SIZE_KB: u64 = 10_240;
+/// Maximum per-user process count (`RLIMIT_NPROC`) accepted by the runner.
+pub(crate) const MAX_PROCESSES: u64 = 4096;
+/// Maximum open file descriptors (`RLIMIT_NOFILE`) accepted by the runner.
+pub(crate) const MAX_OPEN_FILES: u64 = 4096;
+/// Open file cap applied when a scenario does not set `open_files`.
+pub(crate) const DEFAULT_OPEN_FILES: u64 = 128;
+/// Stack cap (`RLIMIT_STACK`) applied when a scenario does not set `stack_kb`,
+/// lowered to `memory_kb` and never raised above the inherited limit.
+pub(crate) const DEFAULT_STACK_KB: u64 = 8192;
+/// Maximum stack size (`RLIMIT_STACK`) accepted by the runner.
+pub(crate) const MAX_STACK_KB: u64 = 65_536;
 /// Maximum number of args accepted by the runner.
 pub(crate) const MAX_ARGS: usize = 256;
 /// Maximum length of a single arg accepted by the runner.
@@ -54,6 +65,20 @@ pub(crate) struct ScenarioLimits {
     pub(crate) cpu_time_ms: u64,
     pub(crate) memory_kb: u64,
     pub(crate) file_size_kb: u64,
+    /// `RLIMIT_NPROC`, applied only when set: it counts every process the
+    /// user owns, so a default could break forking on busy hosts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) max_processes: Option<u64>,
+    #[serde(default = "default_open_files")]
+    pub(crate) open_files: u64,
+    /// `RLIMIT_STACK`; when unset the runner applies [`DEFAULT_STACK_KB`]
+    /// bounded by `memory_kb` and the inherited limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) stack_kb: Option<u64>,
+}
+
+fn default_open_files() -> u64 {
+    DEFAULT_OPEN_FILES
 }
 
 /// Artifact capture settings.
@@ -123,6 +148,22 @@ pub(crate) fn validate_scenario(scenario: &Scenario) -> Option<Vec<String>> {
         MAX_FILE_SIZE_KB,
         &mut errors,
     );
+    if let Some(max_processes) = scenario.limits.max_processes {
+        validate_limit("max_processes", max_processes, MAX_PROCESSES, &mut errors);
+    }
+    validate_limit(
+        "open_files",
+        scenario.limits.open_files,
+        MAX_OPEN_FILES,
+        &mut errors,
+    );
+    if let Some(stack_kb) = scenario.limits.stack_kb {
+        validate_limit("stack_kb", stack_kb, MAX_STACK_KB, &mut errors);
+        // The stack reservation counts against RLIMIT_AS.
+        if stack_kb > scenario.limits.memory_kb {
+            errors.push("stack_kb must not exceed memory_kb".to_string());
+        }
+    }
     if !scenario.artifacts.capture_exit_code {
         errors.push("artifacts.capture_exit_code must be true".to_string());
     }
@@ -141,3 +182,74 @@ fn validate_limit(name: &str, value: u64, max: u64, errors: &mut Vec<String>) {
         errors.push(format!("{name} exceeds max ({max})"));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scenario_with_limits(limits: &str) -> Scenario {
+        serde_json::from_str(&format!(
+            r#"{{
+                "scenario_id": "t",
+                "rationale": "r",
+                "binary": {{ "path": "/bin/true" }},
+                "args": [],
+                "fixture": {{ "id": "fs/empty_dir" }},
+                "limits": {limits},
+                "artifacts": {{
+                    "capture_stdout": true,
+                    "capture_stderr": true,
+                    "capture_exit_code": true
+                }}
+            }}"#
+        ))
+        .unwrap()
+    }
+
+    const BASE_LIMITS: &str =
+        r#""wall_time_ms": 1000, "cpu_time_ms": 1000, "file_size_kb": 1024"#;
+
+    #[test]
+    fn small_memory_without_stack_kb_is_valid() {
+        let scenario = scenario_with_limits(&format!(r#"{{ {BASE_LIMITS}, "memory_kb": 4096 }}"#));
+        assert_eq!(scenario.limits.stack_kb, None);
+        assert_eq!(validate_scenario(&scenario), None);
+    }
+
+    #[test]
+    fn explicit_stack_kb_within_memory_is_valid() {
+        let scenario = scenario_with_limits(&format!(
+            r#"{{ {BASE_LIMITS}, "memory_kb": 65536, "stack_kb": 8192 }}"#
+        ));
+        assert_eq!(validate_scenario(&scenario), None);
+    }
+
+    #[test]
+    fn explicit_stack_kb_above_memory_is_rejected() {
+        let scenario = scenario_with_limits(&format!(
+            r#"{{ {BASE_LIMITS}, "memory_kb": 4096, "stack_kb": 8192 }}"#
+        ));
+        assert_eq!(
+            validate_scenario(&scenario),
+            Some(vec!["stack_kb must not exceed memory_kb".to_string()])
+        );
+    }
+
+    #[test]
+    fn stack_kb_bounds_are_enforced() {
+        let zero = scenario_with_limits(&format!(
+            r#"{{ {BASE_LIMITS}, "memory_kb": 65536, "stack_kb": 0 }}"#
+        ));
+        assert_eq!(
+            validate_scenario(&zero),
+            Some(vec!["stack_kb must be > 0".to_string()])
+        );
+        let huge = scenario_with_limits(&format!(
+            r#"{{ {BASE_LIMITS}, "memory_kb": 262144, "stack_kb": 131072 }}"#
+        ));
+        assert_eq!(
+            validate_scenario(&huge),
+            Some(vec![format!("stack_kb exceeds max ({MAX_STACK_KB})")])
+        );
+    }
+}
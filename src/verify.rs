@@ -0,0 +1,299 @@
+//! Evidence bundle verification: re-hash stored files against `meta.json`.
+
+use anyhow::{anyhow, Context, Result};
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+use crate::binary::{hash_binary, resolve_binary};
+use crate::hashing::{sha256_file, sha256_hex};
+
+/// Result of checking one recorded hash.
+pub(crate) enum CheckStatus {
+    Ok,
+    Mismatch { expected: String, actual: String },
+    /// The file is not part of the bundle (e.g. stdout was not captured).
+    NotStored,
+    /// A file the bundle must contain is absent.
+    Missing,
+    /// The item could not be read or hashed.
+    Error(String),
+}
+
+impl CheckStatus {
+    /// Whether this outcome fails verification.
+    pub(crate) fn is_failure(&self) -> bool {
+        matches!(self, Self::Mismatch { .. } | Self::Missing | Self::Error(_))
+    }
+}
+
+/// A single verification check and its outcome.
+pub(crate) struct Check {
+    pub(crate) name: String,
+    pub(crate) status: CheckStatus,
+}
+
+/// Verify an evidence directory, returning one check per recorded hash.
+///
+/// Stored artifacts (`stdout.txt`, `stderr.txt`, `combined.txt`, or their
+/// `.gz` forms) and `scenario.json` must match their hashes. Which artifacts
+/// are required comes from `scenario.json` `artifacts.capture_*` (both
+/// assumed when it cannot be read) and `meta.json` `artifacts.combined_sha256`;
+/// a required file that is absent fails. The binary is re-hashed when it is
+/// still present at `binary.path`, and the sandbox copy hash must agree with
+/// `binary.sha256`. A `meta.json` that records no hashes at all is an error
+/// rather than a vacuous pass.
+pub(crate) fn verify_evidence(dir: &Path) -> Result<Vec<Check>> {
+    let meta_path = dir.join("meta.json");
+    let bytes =
+        fs::read(&meta_path).with_context(|| format!("read {}", meta_path.display()))?;
+    let meta: Value = serde_json::from_slice(&bytes).context("parse meta.json")?;
+    if !meta.is_object() {
+        return Err(anyhow!("meta.json is not a JSON object"));
+    }
+
+    let mut checks = Vec::new();
+    if let Some(expected) = str_at(&meta, &["scenario_sha256"]) {
+        checks.push(check_file(dir, "scenario.json", expected, true));
+    }
+    let scenario: Option<Value> = fs::read(dir.join("scenario.json"))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+    let captured = |flag: &str| {
+        scenario
+            .as_ref()
+            .and_then(|scenario| scenario.get("artifacts")?.get(flag)?.as_bool())
+            .unwrap_or(true)
+    };
+    for (file, key, required) in [
+        ("stdout.txt", "stdout_sha256", captured("capture_stdout")),
+        ("stderr.txt", "stderr_sha256", captured("capture_stderr")),
+        ("combined.txt", "combined_sha256", true),
+    ] {
+        if let Some(expected) = str_at(&meta, &["artifacts", key]) {
+            checks.push(check_file(dir, file, expected, required));
+        }
+    }
+
+    let binary_hash = str_at(&meta, &["binary", "sha256"]);
+    if let (Some(expected), Some(path)) = (binary_hash, str_at(&meta, &["binary", "path"])) {
+        let status = match resolve_binary(Path::new(path)) {
+            Ok(resolved) => match hash_binary(&resolved) {
+                Ok(actual) => compare(expected, &actual),
+                Err(err) => CheckStatus::Error(format!("{err:#}")),
+            },
+            Err(_) => CheckStatus::NotStored,
+        };
+        checks.push(Check {
+            name: format!("binary {path}"),
+            status,
+        });
+    }
+    if let Some(staged) = str_at(&meta, &["sandbox", "binary_sha256"]) {
+        let status = match binary_hash {
+            Some(expected) => compare(expected, staged),
+            None => CheckStatus::Mismatch {
+                expected: "<missing binary.sha256>".to_string(),
+                actual: staged.to_string(),
+            },
+        };
+        checks.push(Check {
+            name: "sandbox.binary_sha256".to_string(),
+            status,
+        });
+    }
+    if checks.is_empty() {
+        return Err(anyhow!(
+            "{} records no hashes; nothing to verify",
+            meta_path.display()
+        ));
+    }
+    Ok(checks)
+}
+
+/// Hash `name`, or the decompressed contents of `name.gz` when only that exists.
+fn check_file(dir: &Path, name: &str, expected: &str, required: bool) -> Check {
+    let path = dir.join(name);
+    let gz_path = dir.join(format!("{name}.gz"));
+    let status = if path.is_file() {
+        match sha256_file(&path) {
+            Ok(actual) => compare(expected, &actual),
+            Err(err) => CheckStatus::Error(format!("hash {}: {err}", path.display())),
+        }
+    } else if gz_path.is_file() {
+        match read_gz(&gz_path) {
+            Ok(bytes) => compare(expected, &sha256_hex(&bytes)),
+            Err(err) => CheckStatus::Error(format!("{err:#}")),
+        }
+    } else if required {
+        CheckStatus::Missing
+    } else {
+        CheckStatus::NotStored
+    };
+    Check {
+        name: name.to_string(),
+        status,
+    }
+}
+
+/// Decompress a gzipped artifact written by `write_artifact`.
+pub(crate) fn read_gz(path: &Path) -> Result<Vec<u8>> {
+    let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut bytes = Vec::new();
+    GzDecoder::new(file)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("decompress {}", path.display()))?;
+    Ok(bytes)
+}
+
+fn compare(expected: &str, actual: &str) -> CheckStatus {
+    if expected == actual {
+        CheckStatus::Ok
+    } else {
+        CheckStatus::Mismatch {
+            expected: expected.to_string(),
+            actual: actual.to_string(),
+        }
+    }
+}
+
+fn str_at<'a>(value: &'a Value, path: &[&str]) -> Option<&'a str> {
+    path.iter()
+        .try_fold(value, |current, key| current.get(key))
+        .and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evidence::write_artifact;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    const STDOUT: &[u8] = b"hello\n";
+    const STDERR: &[u8] = b"warning\n";
+
+    /// Write a bundle with stdout/stderr artifacts and a matching `meta.json`.
+    fn write_bundle(dir: &Path, capture_stderr: bool, compress: bool) -> Value {
+        let scenario = json!({
+            "artifacts": {"capture_stdout": true, "capture_stderr": capture_stderr},
+        });
+        let scenario_bytes = serde_json::to_vec(&scenario).unwrap();
+        fs::write(dir.join("scenario.json"), &scenario_bytes).unwrap();
+        write_artifact(dir, "stdout.txt", STDOUT, compress).unwrap();
+        if capture_stderr {
+            write_artifact(dir, "stderr.txt", STDERR, compress).unwrap();
+        }
+        let meta = json!({
+            "scenario_sha256": sha256_hex(&scenario_bytes),
+            "artifacts": {
+                "stdout_sha256": sha256_hex(STDOUT),
+                "stderr_sha256": sha256_hex(STDERR),
+            },
+        });
+        write_meta(dir, &meta);
+        meta
+    }
+
+    fn write_meta(dir: &Path, meta: &Value) {
+        fs::write(dir.join("meta.json"), serde_json::to_vec(meta).unwrap()).unwrap();
+    }
+
+    fn statuses(dir: &Path) -> Vec<(String, &'static str)> {
+        verify_evidence(dir)
+            .unwrap()
+            .into_iter()
+            .map(|check| {
+                let status = match check.status {
+                    CheckStatus::Ok => "ok",
+                    CheckStatus::Mismatch { .. } => "mismatch",
+                    CheckStatus::NotStored => "skipped",
+                    CheckStatus::Missing => "missing",
+                    CheckStatus::Error(_) => "error",
+                };
+                (check.name, status)
+            })
+            .collect()
+    }
+
+    fn expect(pairs: &[(&str, &'static str)]) -> Vec<(String, &'static str)> {
+        pairs.iter().map(|(name, status)| (name.to_string(), *status)).collect()
+    }
+
+    #[test]
+    fn matching_bundle_verifies() {
+        let dir = TempDir::new().unwrap();
+        write_bundle(dir.path(), true, false);
+        assert_eq!(
+            statuses(dir.path()),
+            expect(&[("scenario.json", "ok"), ("stdout.txt", "ok"), ("stderr.txt", "ok")])
+        );
+    }
+
+    #[test]
+    fn compressed_artifacts_verify() {
+        let dir = TempDir::new().unwrap();
+        write_bundle(dir.path(), true, true);
+        assert!(dir.path().join("stdout.txt.gz").is_file());
+        assert_eq!(
+            statuses(dir.path()),
+            expect(&[("scenario.json", "ok"), ("stdout.txt", "ok"), ("stderr.txt", "ok")])
+        );
+    }
+
+    #[test]
+    fn edited_artifact_is_a_mismatch() {
+        let dir = TempDir::new().unwrap();
+        write_bundle(dir.path(), true, false);
+        fs::write(dir.path().join("stdout.txt"), b"tampered\n").unwrap();
+        assert_eq!(
+            statuses(dir.path()),
+            expect(&[("scenario.json", "ok"), ("stdout.txt", "mismatch"), ("stderr.txt", "ok")])
+        );
+    }
+
+    #[test]
+    fn deleted_required_artifact_is_missing() {
+        let dir = TempDir::new().unwrap();
+        write_bundle(dir.path(), true, false);
+        fs::remove_file(dir.path().join("stderr.txt")).unwrap();
+        let checks = statuses(dir.path());
+        assert_eq!(checks[2], ("stderr.txt".to_string(), "missing"));
+    }
+
+    #[test]
+    fn uncaptured_stream_is_skipped() {
+        let dir = TempDir::new().unwrap();
+        write_bundle(dir.path(), false, false);
+        assert_eq!(
+            statuses(dir.path()),
+            expect(&[("scenario.json", "ok"), ("stdout.txt", "ok"), ("stderr.txt", "skipped")])
+        );
+    }
+
+    #[test]
+    fn sandbox_hash_must_match_binary_hash() {
+        let dir = TempDir::new().unwrap();
+        let mut meta = write_bundle(dir.path(), true, false);
+        meta["binary"] = json!({"sha256": "aa"});
+        meta["sandbox"] = json!({"binary_sha256": "bb"});
+        write_meta(dir.path(), &meta);
+        let checks = statuses(dir.path());
+        assert_eq!(checks.last().unwrap(), &("sandbox.binary_sha256".to_string(), "mismatch"));
+
+        meta["sandbox"] = json!({"binary_sha256": "aa"});
+        write_meta(dir.path(), &meta);
+        let checks = statuses(dir.path());
+        assert_eq!(checks.last().unwrap(), &("sandbox.binary_sha256".to_string(), "ok"));
+    }
+
+    #[test]
+    fn bundle_without_hashes_is_an_error() {
+        let dir = TempDir::new().unwrap();
+        write_meta(dir.path(), &json!({"run": "x"}));
+        let err = verify_evidence(dir.path()).err().expect("no hashes should fail");
+        assert!(err.to_string().contains("nothing to verify"), "{err:#}");
+    }
+}